@@ -0,0 +1,458 @@
+use crate::theme::TerminalTheme;
+use alacritty_terminal::grid::{Dimensions, Grid};
+use alacritty_terminal::index::{Column, Line};
+use alacritty_terminal::term::cell::{Cell, Flags};
+use alacritty_terminal::term::color::Colors;
+use alacritty_terminal::vte::ansi::{Color, NamedColor};
+use egui::Color32;
+use std::fmt::Write;
+
+/// Cell flags that change how text looks.
+const STYLE_FLAGS: Flags = Flags::BOLD
+    .union(Flags::DIM)
+    .union(Flags::ITALIC)
+    .union(Flags::ALL_UNDERLINES)
+    .union(Flags::INVERSE)
+    .union(Flags::HIDDEN)
+    .union(Flags::STRIKEOUT);
+
+/// File format of an exported terminal history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    /// Plain text with trailing whitespace trimmed.
+    #[default]
+    Text,
+    /// Text with SGR escape sequences for colors and attributes.
+    Ansi,
+    /// Standalone HTML page colored with the terminal theme.
+    Html,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] =
+        [ExportFormat::Text, ExportFormat::Ansi, ExportFormat::Html];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Text => "Plain text",
+            ExportFormat::Ansi => "ANSI text",
+            ExportFormat::Html => "HTML",
+        }
+    }
+
+    /// Usual file extension, without the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Text => "txt",
+            ExportFormat::Ansi => "ans",
+            ExportFormat::Html => "html",
+        }
+    }
+}
+
+/// Colors and attributes shared by a run of cells.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Style {
+    fg: Color,
+    bg: Color,
+    flags: Flags,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            fg: Color::Named(NamedColor::Foreground),
+            bg: Color::Named(NamedColor::Background),
+            flags: Flags::empty(),
+        }
+    }
+}
+
+impl Style {
+    fn of(cell: &Cell) -> Self {
+        Self {
+            fg: cell.fg,
+            bg: cell.bg,
+            flags: cell.flags & STYLE_FLAGS,
+        }
+    }
+}
+
+/// A line of output, wrapped rows joined, as runs of equally styled text.
+type StyledLine = Vec<(Style, String)>;
+
+/// Copy of a terminal's history and screen, with the colors its program
+/// changed, that can be serialized away from the terminal.
+#[derive(Clone)]
+pub struct HistorySnapshot {
+    pub(crate) grid: Grid<Cell>,
+    pub(crate) colors: Colors,
+}
+
+impl HistorySnapshot {
+    /// Serialize the history as `format`; HTML is colored with `theme`
+    /// and the snapshot's OSC color overrides.
+    pub fn export(
+        &self,
+        format: ExportFormat,
+        theme: &TerminalTheme,
+    ) -> String {
+        export_grid(&self.grid, format, theme, &self.colors)
+    }
+}
+
+/// Serialize the grid's history and screen.
+fn export_grid(
+    grid: &Grid<Cell>,
+    format: ExportFormat,
+    theme: &TerminalTheme,
+    colors: &Colors,
+) -> String {
+    let lines = styled_lines(grid);
+    match format {
+        ExportFormat::Text => to_text(&lines),
+        ExportFormat::Ansi => to_ansi(&lines),
+        ExportFormat::Html => to_html(&lines, theme, colors),
+    }
+}
+
+fn styled_lines(grid: &Grid<Cell>) -> Vec<StyledLine> {
+    let mut lines = Vec::new();
+    let mut line = StyledLine::new();
+    let last_column = grid.last_column();
+
+    for row_index in grid.topmost_line().0..=grid.bottommost_line().0 {
+        let row = &grid[Line(row_index)];
+        let wrapped = row[last_column].flags.contains(Flags::WRAPLINE);
+        let used = if wrapped {
+            grid.columns()
+        } else {
+            (0..grid.columns())
+                .rposition(|column| !is_blank(&row[Column(column)]))
+                .map_or(0, |column| column + 1)
+        };
+
+        for cell in (0..used).map(|column| &row[Column(column)]) {
+            if cell.flags.intersects(
+                Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER,
+            ) {
+                continue;
+            }
+
+            let style = Style::of(cell);
+            let text = match line.last_mut() {
+                Some((last_style, text)) if *last_style == style => text,
+                _ => {
+                    line.push((style, String::new()));
+                    &mut line.last_mut().unwrap().1
+                },
+            };
+            text.push(cell.c);
+            text.extend(cell.zerowidth().into_iter().flatten());
+        }
+
+        if !wrapped {
+            lines.push(std::mem::take(&mut line));
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    while lines
+        .last()
+        .is_some_and(|line| line_text(line).trim().is_empty())
+    {
+        lines.pop();
+    }
+    lines
+}
+
+/// True for cells that look the same as an untouched one.
+fn is_blank(cell: &Cell) -> bool {
+    cell.c == ' '
+        && cell.bg == Color::Named(NamedColor::Background)
+        && !cell
+            .flags
+            .intersects(Flags::INVERSE | Flags::ALL_UNDERLINES)
+        && cell.zerowidth().is_none()
+}
+
+fn line_text(line: &StyledLine) -> String {
+    line.iter().map(|(_, text)| text.as_str()).collect()
+}
+
+fn to_text(lines: &[StyledLine]) -> String {
+    let mut output = String::new();
+    for line in lines {
+        output.push_str(line_text(line).trim_end());
+        output.push('\n');
+    }
+    output
+}
+
+fn to_ansi(lines: &[StyledLine]) -> String {
+    let mut output = String::new();
+    for line in lines {
+        let mut current = Style::default();
+        for (style, text) in line {
+            if *style != current {
+                output.push_str(&sgr(style));
+                current = *style;
+            }
+            output.push_str(text);
+        }
+        if current != Style::default() {
+            output.push_str("\x1b[0m");
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// SGR sequence that resets the attributes and then applies `style`.
+fn sgr(style: &Style) -> String {
+    let mut params = vec![String::from("0")];
+    let flag_params = [
+        (Flags::BOLD, "1"),
+        (Flags::DIM, "2"),
+        (Flags::ITALIC, "3"),
+        (Flags::UNDERLINE, "4"),
+        (Flags::DOUBLE_UNDERLINE, "21"),
+        (Flags::UNDERCURL, "4:3"),
+        (Flags::DOTTED_UNDERLINE, "4:4"),
+        (Flags::DASHED_UNDERLINE, "4:5"),
+        (Flags::INVERSE, "7"),
+        (Flags::HIDDEN, "8"),
+        (Flags::STRIKEOUT, "9"),
+    ];
+    for (flag, param) in flag_params {
+        if style.flags.contains(flag) {
+            params.push(param.to_string());
+        }
+    }
+    params.extend(color_params(style.fg, 30));
+    params.extend(color_params(style.bg, 40));
+
+    format!("\x1b[{}m", params.join(";"))
+}
+
+/// SGR parameter for a foreground (`base` 30) or background (`base` 40)
+/// color, `None` for the terminal's default colors.
+fn color_params(color: Color, base: u8) -> Option<String> {
+    match color {
+        Color::Named(named) => {
+            let index = named as usize;
+            let index = match index {
+                0..=15 => index,
+                // Dim colors are the normal ones drawn with the dim flag
+                _ if (NamedColor::DimBlack as usize
+                    ..=NamedColor::DimWhite as usize)
+                    .contains(&index) =>
+                {
+                    index - NamedColor::DimBlack as usize
+                },
+                _ => return None,
+            } as u8;
+            Some(if index < 8 {
+                (base + index).to_string()
+            } else {
+                (base + 60 + index - 8).to_string()
+            })
+        },
+        Color::Indexed(index) => Some(format!("{};5;{}", base + 8, index)),
+        Color::Spec(rgb) => {
+            Some(format!("{};2;{};{};{}", base + 8, rgb.r, rgb.g, rgb.b))
+        },
+    }
+}
+
+fn to_html(
+    lines: &[StyledLine],
+    theme: &TerminalTheme,
+    colors: &Colors,
+) -> String {
+    let default_fg = theme
+        .get_color_with_overrides(Color::Named(NamedColor::Foreground), colors);
+    let default_bg = theme
+        .get_color_with_overrides(Color::Named(NamedColor::Background), colors);
+    let mut output = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Terminal output</title>\n<style>\n\
+         body {{ margin: 0; background: {bg}; color: {fg}; }}\n\
+         pre {{ margin: 0; padding: 8px; font-family: monospace; }}\n\
+         </style>\n</head>\n<body>\n<pre>",
+        bg = css_color(default_bg),
+        fg = css_color(default_fg),
+    );
+
+    for line in lines {
+        for (style, text) in line {
+            let css = span_css(style, theme, colors, default_fg, default_bg);
+            if css.is_empty() {
+                output.push_str(&escape_html(text));
+            } else {
+                let _ = write!(
+                    output,
+                    "<span style=\"{}\">{}</span>",
+                    css,
+                    escape_html(text)
+                );
+            }
+        }
+        output.push('\n');
+    }
+
+    output.push_str("</pre>\n</body>\n</html>\n");
+    output
+}
+
+/// Inline CSS for a run, drawn the way the terminal view draws it.
+fn span_css(
+    style: &Style,
+    theme: &TerminalTheme,
+    colors: &Colors,
+    default_fg: Color32,
+    default_bg: Color32,
+) -> String {
+    let mut fg = theme.get_color_with_overrides(style.fg, colors);
+    let mut bg = theme.get_color_with_overrides(style.bg, colors);
+    if style.flags.intersects(Flags::DIM) {
+        fg = fg.linear_multiply(0.7);
+    }
+    if style.flags.contains(Flags::INVERSE) {
+        std::mem::swap(&mut fg, &mut bg);
+    }
+    if style.flags.contains(Flags::HIDDEN) {
+        fg = bg;
+    }
+
+    let mut css = Vec::new();
+    if fg != default_fg {
+        css.push(format!("color: {}", css_color(fg)));
+    }
+    if bg != default_bg {
+        css.push(format!("background: {}", css_color(bg)));
+    }
+    if style.flags.contains(Flags::BOLD) {
+        css.push(String::from("font-weight: bold"));
+    }
+    if style.flags.contains(Flags::ITALIC) {
+        css.push(String::from("font-style: italic"));
+    }
+
+    let mut decorations = Vec::new();
+    if style.flags.intersects(Flags::ALL_UNDERLINES) {
+        decorations.push("underline");
+    }
+    if style.flags.contains(Flags::STRIKEOUT) {
+        decorations.push("line-through");
+    }
+    if !decorations.is_empty() {
+        css.push(format!("text-decoration: {}", decorations.join(" ")));
+    }
+
+    css.join("; ")
+}
+
+fn css_color(color: Color32) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::term::{test::TermSize, Config, Term};
+    use alacritty_terminal::vte::ansi::{Processor, Rgb};
+
+    fn grid_with(output: &str) -> Grid<Cell> {
+        let mut term =
+            Term::new(Config::default(), &TermSize::new(10, 4), VoidListener);
+        let mut processor: Processor = Processor::new();
+        for byte in output.bytes() {
+            processor.advance(&mut term, byte);
+        }
+        term.grid().clone()
+    }
+
+    #[test]
+    fn text_joins_wrapped_rows_and_trims() {
+        let grid = grid_with("one   \r\n0123456789abc\r\n\r\n");
+        let text = export_grid(
+            &grid,
+            ExportFormat::Text,
+            &Default::default(),
+            &Colors::default(),
+        );
+        assert_eq!(text, "one\n0123456789abc\n");
+    }
+
+    #[test]
+    fn ansi_reproduces_colors_and_attributes() {
+        let grid = grid_with("\x1b[1;31mred\x1b[0m plain \x1b[38;5;200mpink");
+        let ansi = export_grid(
+            &grid,
+            ExportFormat::Ansi,
+            &Default::default(),
+            &Colors::default(),
+        );
+        assert_eq!(
+            ansi,
+            "\x1b[0;1;31mred\x1b[0m plain \x1b[0;38;5;200mpink\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn html_uses_theme_and_escapes() {
+        let grid = grid_with("\x1b[4m<a>\x1b[0m & b");
+        let html = export_grid(
+            &grid,
+            ExportFormat::Html,
+            &Default::default(),
+            &Colors::default(),
+        );
+        assert!(html.contains("background: #181818; color: #d8d8d8;"));
+        assert!(html.contains(
+            "<pre><span style=\"text-decoration: underline\">&lt;a&gt;</span> &amp; b\n</pre>"
+        ));
+    }
+
+    #[test]
+    fn html_uses_osc_color_overrides() {
+        let mut colors = Colors::default();
+        colors[NamedColor::Background as usize] = Some(Rgb {
+            r: 0,
+            g: 0,
+            b: 0x80,
+        });
+        colors[NamedColor::Red as usize] = Some(Rgb {
+            r: 0xff,
+            g: 0x80,
+            b: 0,
+        });
+        let grid = grid_with("\x1b[31mred");
+        let html = export_grid(
+            &grid,
+            ExportFormat::Html,
+            &Default::default(),
+            &colors,
+        );
+        assert!(html.contains("background: #000080;"));
+        assert!(html.contains("<span style=\"color: #ff8000\">red</span>"));
+    }
+}
@@ -1,33 +1,50 @@
+pub mod export;
+pub mod search;
 pub mod settings;
 
+use crate::theme::TerminalTheme;
 use crate::types::Size;
 use alacritty_terminal::event::{
     Event, EventListener, Notify, OnResize, WindowSize,
 };
 use alacritty_terminal::event_loop::{EventLoop, Msg, Notifier};
 use alacritty_terminal::grid::{Dimensions, Scroll};
-use alacritty_terminal::index::{Column, Direction, Line, Point, Side};
+use alacritty_terminal::index::{
+    Boundary, Column, Direction, Line, Point, Side,
+};
 use alacritty_terminal::selection::{
     Selection, SelectionRange, SelectionType as AlacrittySelectionType,
 };
 use alacritty_terminal::sync::FairMutex;
 use alacritty_terminal::term::search::{Match, RegexIter, RegexSearch};
 use alacritty_terminal::term::{
-    self, cell::Cell, test::TermSize, viewport_to_point, Term, TermMode,
+    self, cell::Cell, color::Colors, test::TermSize, viewport_to_point, Term,
+    TermMode,
 };
+use alacritty_terminal::vi_mode::ViMotion as AlacrittyViMotion;
+use alacritty_terminal::vte::ansi::{ClearMode, Handler, Processor, Rgb};
 use alacritty_terminal::{tty, Grid};
 use egui::Modifiers;
-use settings::BackendSettings;
+use export::HistorySnapshot;
+use search::{
+    SearchOptions, SearchStatus, MAX_COUNTED_LINES, MAX_COUNTED_MATCHES,
+    RECOUNT_INTERVAL,
+};
+use settings::{BackendSettings, ScrollbackLimit};
 use std::borrow::Cow;
-use std::cmp::min;
+use std::cmp::{max, min};
 use std::io::Result;
 use std::ops::{Index, RangeInclusive};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc::Sender;
 use std::sync::{mpsc, Arc};
+use std::time::Instant;
 
 pub type TerminalMode = TermMode;
 pub type PtyEvent = Event;
 pub type SelectionType = AlacrittySelectionType;
+pub type ViMotion = AlacrittyViMotion;
 
 #[derive(Debug, Clone)]
 pub enum BackendCommand {
@@ -36,6 +53,7 @@ pub enum BackendCommand {
     Resize(Size, Size),
     SelectStart(SelectionType, f32, f32),
     SelectUpdate(f32, f32),
+    SelectScroll(i32, f32, f32),
     ProcessLink(LinkAction, Point),
     MouseReport(MouseButton, Modifiers, Point, bool),
 }
@@ -79,7 +97,7 @@ pub enum LinkAction {
     Open,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct TerminalSize {
     pub cell_width: u16,
     pub cell_height: u16,
@@ -141,6 +159,116 @@ pub struct TerminalBackend {
     size: TerminalSize,
     notifier: Notifier,
     last_content: RenderableContent,
+    search: Option<Search>,
+    /// Bumped whenever the grid changes, so the search count knows when
+    /// it went stale.
+    grid_generation: Arc<AtomicU64>,
+}
+
+/// Active scrollback search.
+struct Search {
+    regex: RegexSearch,
+    focused: Option<Match>,
+    status: SearchStatus,
+    /// Grid generation the status was counted at, and when.
+    counted: Option<(u64, Instant)>,
+}
+
+impl Search {
+    fn new(
+        query: &str,
+        options: SearchOptions,
+    ) -> std::result::Result<Self, String> {
+        let pattern = search::search_pattern(query, options);
+        let regex = RegexSearch::new(&pattern).map_err(|e| e.to_string())?;
+        Ok(Self {
+            regex,
+            focused: None,
+            status: SearchStatus::default(),
+            counted: None,
+        })
+    }
+
+    fn step<T: EventListener>(
+        &mut self,
+        term: &mut Term<T>,
+        forward: bool,
+        generation: u64,
+    ) {
+        let previous = self.focused.as_ref().map(|focused| *focused.start());
+        let (direction, side) = if forward {
+            (Direction::Right, Side::Left)
+        } else {
+            (Direction::Left, Side::Right)
+        };
+        let origin = match &self.focused {
+            Some(focused) if forward => {
+                focused.end().add(term, Boundary::None, 1)
+            },
+            Some(focused) => focused.start().sub(term, Boundary::None, 1),
+            // Start from the bottom so the newest output is found first
+            None => Point::new(term.bottommost_line(), term.last_column()),
+        };
+
+        self.focused =
+            term.search_next(&mut self.regex, origin, direction, side, None);
+        if let Some(focused) = &self.focused {
+            if term.mode().contains(TermMode::VI) {
+                term.vi_goto_point(*focused.start());
+            } else {
+                term.scroll_to_point(*focused.start());
+            }
+        }
+
+        let focused = self.focused.as_ref().map(|focused| *focused.start());
+        let stepped = match (self.status.current, previous, focused) {
+            (Some(current), Some(previous), Some(focused))
+                if self.status.complete
+                    && self
+                        .counted
+                        .is_some_and(|(counted, _)| counted == generation) =>
+            {
+                let wrapped = if forward {
+                    focused <= previous
+                } else {
+                    focused >= previous
+                };
+                Some(match (forward, wrapped) {
+                    (true, false) => current + 1,
+                    (false, false) => current - 1,
+                    (true, true) => 1,
+                    (false, true) => self.status.total,
+                })
+            },
+            _ => None,
+        };
+        match stepped {
+            // The grid is unchanged, so the neighbouring match is next
+            Some(current) => self.status.current = Some(current),
+            None => self.recount(term, generation),
+        }
+    }
+
+    /// Count the matches again if the grid changed since the last count and
+    /// that count is older than `RECOUNT_INTERVAL`.
+    fn refresh<T>(&mut self, term: &Term<T>, generation: u64) {
+        let stale = self.counted.is_none_or(|(counted, counted_at)| {
+            counted != generation && counted_at.elapsed() >= RECOUNT_INTERVAL
+        });
+        if stale {
+            self.recount(term, generation);
+        }
+    }
+
+    fn recount<T>(&mut self, term: &Term<T>, generation: u64) {
+        self.status = count_matches(
+            term,
+            &mut self.regex,
+            self.focused.as_ref(),
+            MAX_COUNTED_LINES,
+        );
+        self.counted = Some((generation, Instant::now()));
+    }
 }
 
 impl TerminalBackend {
@@ -156,11 +284,16 @@ impl TerminalBackend {
             env: settings.env,
             ..tty::Options::default()
         };
-        let config = term::Config::default();
+        let config = term::Config {
+            scrolling_history: settings.scrollback.history_size(),
+            ..term::Config::default()
+        };
         let terminal_size = TerminalSize::default();
         let pty = tty::new(&pty_config, terminal_size.into(), id)?;
         let pty_id = pty.child().id();
         let (event_sender, event_receiver) = mpsc::channel();
+        let grid_generation = Arc::new(AtomicU64::new(0));
+        let output_generation = grid_generation.clone();
         let event_proxy = EventProxy(event_sender);
         let mut term = Term::new(config, &terminal_size, event_proxy.clone());
         let initial_content = RenderableContent {
@@ -170,6 +303,10 @@ impl TerminalBackend {
             terminal_size,
             cursor: term.grid_mut().cursor_cell().clone(),
             hovered_hyperlink: None,
+            search_matches: Vec::new(),
+            focused_search_match: None,
+            vi_cursor: None,
+            colors: *term.colors(),
         };
         let term = Arc::new(FairMutex::new(term));
         let pty_event_loop =
@@ -181,6 +318,9 @@ impl TerminalBackend {
             .name(format!("pty_event_subscription_{}", id))
             .spawn(move || loop {
                 if let Ok(event) = event_receiver.recv() {
+                    if let Event::Wakeup = event {
+                        output_generation.fetch_add(1, Ordering::Relaxed);
+                    }
                     pty_event_proxy_sender
                         .send((id, event.clone()))
                         .unwrap_or_else(|_| {
@@ -201,6 +341,8 @@ impl TerminalBackend {
             size: terminal_size,
             notifier,
             last_content: initial_content,
+            search: None,
+            grid_generation,
         })
     }
 
@@ -224,6 +366,10 @@ impl TerminalBackend {
             BackendCommand::SelectUpdate(x, y) => {
                 self.update_selection(&mut term, x, y);
             },
+            BackendCommand::SelectScroll(delta, x, y) => {
+                term.scroll_display(Scroll::Delta(delta));
+                self.update_selection(&mut term, x, y);
+            },
             BackendCommand::ProcessLink(link_action, point) => {
                 self.process_link_action(&term, link_action, point);
             },
@@ -248,17 +394,9 @@ impl TerminalBackend {
         viewport_to_point(display_offset, Point::new(line, col))
     }
 
+    /// Text of the selection, history included.
     pub fn selectable_content(&self) -> String {
-        let content = self.last_content();
-        let mut result = String::new();
-        if let Some(range) = content.selectable_range {
-            for indexed in content.grid.display_iter() {
-                if range.contains(indexed.point) {
-                    result.push(indexed.c);
-                }
-            }
-        }
-        result
+        selection_to_text(&self.term.lock())
     }
 
     pub fn sync(&mut self) -> &RenderableContent {
@@ -275,9 +413,143 @@ impl TerminalBackend {
         self.last_content.cursor = cursor.clone();
         self.last_content.terminal_mode = *terminal.mode();
         self.last_content.terminal_size = self.size;
+        self.last_content.vi_cursor = terminal
+            .mode()
+            .contains(TermMode::VI)
+            .then_some(terminal.vi_mode_cursor.point);
+        self.last_content.colors = *terminal.colors();
+        match self.search.as_mut() {
+            Some(search) => {
+                search.refresh(
+                    &terminal,
+                    self.grid_generation.load(Ordering::Relaxed),
+                );
+                self.last_content.search_matches =
+                    visible_regex_match_iter(&terminal, &mut search.regex)
+                        .collect();
+                self.last_content.focused_search_match = search.focused.clone();
+            },
+            None => {
+                self.last_content.search_matches.clear();
+                self.last_content.focused_search_match = None;
+            },
+        }
         self.last_content()
     }
 
+    /// Search the whole history for `query` and focus the newest match.
+    /// An empty query ends the search; an invalid regex is returned as an
+    /// error.
+    pub fn search(
+        &mut self,
+        query: &str,
+        options: SearchOptions,
+    ) -> std::result::Result<(), String> {
+        if query.is_empty() {
+            self.clear_search();
+            return Ok(());
+        }
+
+        self.search = Some(Search::new(query, options)?);
+        self.search_step(false);
+        Ok(())
+    }
+
+    /// Focus the next match below (`forward`) or above the focused one,
+    /// wrapping around, and scroll it into view.
+    pub fn search_step(&mut self, forward: bool) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+        let generation = self.grid_generation.load(Ordering::Relaxed);
+        search.step(&mut self.term.lock(), forward, generation);
+    }
+
+    /// Stop searching and remove the highlights.
+    pub fn clear_search(&mut self) {
+        self.search = None;
+    }
+
+    /// Match counter of the active search.
+    pub fn search_status(&self) -> Option<SearchStatus> {
+        self.search.as_ref().map(|search| search.status)
+    }
+
+    /// Enter or leave vi mode; leaving drops the selection.
+    pub fn toggle_vi_mode(&mut self) {
+        let mut term = self.term.lock();
+        if term.mode().contains(TermMode::VI) {
+            term.selection = None;
+        }
+        term.toggle_vi_mode();
+    }
+
+    pub fn is_vi_mode(&self) -> bool {
+        self.term.lock().mode().contains(TermMode::VI)
+    }
+
+    /// Move the vi mode cursor, extending the selection with it.
+    pub fn vi_motion(&mut self, motion: ViMotion) {
+        self.term.lock().vi_motion(motion);
+    }
+
+    /// Start a selection of `selection_type` at the vi mode cursor, switch
+    /// the running selection to it, or drop the selection when it already
+    /// has that type.
+    pub fn vi_select(&mut self, selection_type: SelectionType) {
+        let mut term = self.term.lock();
+        let point = term.vi_mode_cursor.point;
+        match &mut term.selection {
+            Some(selection)
+                if selection.ty == selection_type && !selection.is_empty() =>
+            {
+                term.selection = None;
+            },
+            Some(selection) if !selection.is_empty() => {
+                selection.ty = selection_type;
+            },
+            _ => {
+                term.selection =
+                    Some(Selection::new(selection_type, point, Side::Left));
+            },
+        }
+        if let Some(selection) = &mut term.selection {
+            selection.include_all();
+        }
+    }
+
+    /// Drop the current selection.
+    pub fn clear_selection(&mut self) {
+        self.term.lock().selection = None;
+    }
+
+    /// Drop the scrollback, keeping the visible screen.
+    pub fn clear_history(&mut self) {
+        self.term.lock().clear_screen(ClearMode::Saved);
+        self.grid_generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Change how many lines of history are kept; shrinking drops the
+    /// oldest lines right away.
+    pub fn set_scrollback(&mut self, scrollback: ScrollbackLimit) {
+        self.term.lock().set_options(term::Config {
+            scrolling_history: scrollback.history_size(),
+            ..term::Config::default()
+        });
+    }
+
+    /// Answer a `PtyEvent::ColorRequest` (OSC 4/10/11/12 query) with the
+    /// color the terminal currently draws for `index`.
+    pub fn answer_color_request(
+        &self,
+        theme: &TerminalTheme,
+        index: usize,
+        format: impl Fn(Rgb) -> String,
+    ) {
+        let color = theme.get_indexed_color(index, self.term.lock().colors());
+        self.write(format(color).into_bytes());
+    }
+
     pub fn last_content(&self) -> &RenderableContent {
         &self.last_content
     }
@@ -290,6 +562,50 @@ impl TerminalBackend {
         self.pty_id
     }
 
+    /// Current working directory of the shell process.
+    pub fn working_directory(&self) -> Option<PathBuf> {
+        process_working_directory(self.pty_id)
+    }
+
+    /// Command line of the job in the terminal's foreground, or `None`
+    /// while the shell itself is in the foreground.
+    pub fn foreground_process(&self) -> Option<String> {
+        foreground_process(self.pty_id)
+    }
+
+    /// Approximate bytes held by the terminal grid, history included, and
+    /// by the copy of it kept for rendering.
+    pub fn memory_usage(&self) -> usize {
+        let term = self.term.lock();
+        grid_memory(term.grid()) + grid_memory(&self.last_content.grid)
+    }
+
+    /// Plain text of the last `max_lines` lines of history and screen,
+    /// with wrapped lines joined and trailing blanks trimmed.
+    pub fn last_lines_text(&self, max_lines: usize) -> String {
+        last_lines_to_text(&self.term.lock(), max_lines)
+    }
+
+    /// Copy of the whole history and screen for exporting, so it can be
+    /// serialized without holding the terminal lock.
+    pub fn history_snapshot(&self) -> HistorySnapshot {
+        let term = self.term.lock();
+        HistorySnapshot {
+            grid: term.grid().clone(),
+            colors: *term.colors(),
+        }
+    }
+
+    /// Feed output into the terminal as if the shell had printed it.
+    pub fn replay_output(&mut self, output: &[u8]) {
+        let mut term = self.term.lock();
+        let mut processor: Processor = Processor::new();
+        for byte in output {
+            processor.advance(&mut *term, *byte);
+        }
+        self.grid_generation.fetch_add(1, Ordering::Relaxed);
+    }
+
     fn process_link_action(
         &mut self,
         terminal: &Term<EventProxy>,
@@ -465,30 +781,22 @@ impl TerminalBackend {
         layout_size: Size,
         font_size: Size,
     ) {
-        if layout_size == self.size.layout_size
-            && font_size.width as u16 == self.size.cell_width
-            && font_size.height as u16 == self.size.cell_height
-        {
+        let Some(size) = terminal_size(layout_size, font_size) else {
+            return;
+        };
+        if size == self.size {
             return;
         }
 
-        let lines = (layout_size.height / font_size.height.floor()) as u16;
-        let cols = (layout_size.width / font_size.width.floor()) as u16;
-        if lines > 0 && cols > 0 {
-            self.size = TerminalSize {
-                layout_size,
-                cell_height: font_size.height as u16,
-                cell_width: font_size.width as u16,
-                num_lines: lines,
-                num_cols: cols,
-            };
-
-            self.notifier.on_resize(self.size.into());
-            terminal.resize(TermSize::new(
-                self.size.num_cols as usize,
-                self.size.num_lines as usize,
-            ));
-        }
+        // A zoom changes the cell size, so the grid reflows even when the
+        // layout stays the same
+        self.size = size;
+        self.notifier.on_resize(self.size.into());
+        terminal.resize(TermSize::new(
+            self.size.num_cols as usize,
+            self.size.num_lines as usize,
+        ));
+        self.grid_generation.fetch_add(1, Ordering::Relaxed);
     }
 
     fn write<I: Into<Cow<'static, [u8]>>>(&self, input: I) {
@@ -532,6 +840,260 @@ impl TerminalBackend {
     }
 }
 
+#[cfg(target_os = "linux")]
+fn process_working_directory(pid: u32) -> Option<PathBuf> {
+    std::fs::read_link(format!("/proc/{}/cwd", pid)).ok()
+}
+
+#[cfg(target_os = "macos")]
+fn process_working_directory(pid: u32) -> Option<PathBuf> {
+    use std::ffi::{CStr, OsStr};
+    use std::os::unix::ffi::OsStrExt;
+
+    let info: libc::proc_vnodepathinfo =
+        proc_info(pid, libc::PROC_PIDVNODEPATHINFO)?;
+    // SAFETY: the kernel writes a NUL-terminated path into the zeroed
+    // MAXPATHLEN buffer.
+    let path =
+        unsafe { CStr::from_ptr(info.pvi_cdir.vip_path.as_ptr().cast()) };
+    Some(PathBuf::from(OsStr::from_bytes(path.to_bytes())))
+        .filter(|path| !path.as_os_str().is_empty())
+}
+
+/// `proc_pidinfo` of the given flavor, `None` when the process is gone or
+/// not ours.
+#[cfg(target_os = "macos")]
+fn proc_info<T>(pid: u32, flavor: libc::c_int) -> Option<T> {
+    let size = std::mem::size_of::<T>() as libc::c_int;
+    let mut info = std::mem::MaybeUninit::<T>::zeroed();
+    // SAFETY: the buffer is `size` bytes long; `T` is the plain C struct
+    // the kernel fills in for `flavor`.
+    let written = unsafe {
+        libc::proc_pidinfo(
+            pid as libc::c_int,
+            flavor,
+            0,
+            info.as_mut_ptr().cast(),
+            size,
+        )
+    };
+    // SAFETY: the kernel filled in the whole struct.
+    (written == size).then(|| unsafe { info.assume_init() })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn process_working_directory(_pid: u32) -> Option<PathBuf> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn foreground_process(pid: u32) -> Option<String> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let foreground_group = parse_foreground_group(&stat)?;
+    if foreground_group == pid {
+        return None;
+    }
+
+    let cmdline =
+        std::fs::read(format!("/proc/{}/cmdline", foreground_group)).ok()?;
+    Some(format_command_line(&cmdline)).filter(|command| !command.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+fn foreground_process(pid: u32) -> Option<String> {
+    use std::ffi::CStr;
+
+    let shell: libc::proc_bsdinfo = proc_info(pid, libc::PROC_PIDTBSDINFO)?;
+    let foreground_group = shell.e_tpgid;
+    if foreground_group == 0 || foreground_group == shell.pbi_pgid {
+        return None;
+    }
+
+    process_arguments(foreground_group)
+        .map(|arguments| format_command_line(&arguments))
+        .or_else(|| {
+            let leader: libc::proc_bsdinfo =
+                proc_info(foreground_group, libc::PROC_PIDTBSDINFO)?;
+            // SAFETY: `pbi_comm` is NUL-terminated by the kernel.
+            let name = unsafe { CStr::from_ptr(leader.pbi_comm.as_ptr()) };
+            Some(name.to_string_lossy().into_owned())
+        })
+        .filter(|command| !command.is_empty())
+}
+
+/// NUL-separated argv of a process, read with `KERN_PROCARGS2`.
+#[cfg(target_os = "macos")]
+fn process_arguments(pid: u32) -> Option<Vec<u8>> {
+    let mut mib = [libc::CTL_KERN, libc::KERN_PROCARGS2, pid as libc::c_int];
+    let mut size: libc::size_t = 0;
+    // SAFETY: a null buffer asks sysctl for the size only.
+    let queried = unsafe {
+        libc::sysctl(
+            mib.as_mut_ptr(),
+            mib.len() as libc::c_uint,
+            std::ptr::null_mut(),
+            &mut size,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if queried != 0 {
+        return None;
+    }
+
+    let mut buffer = vec![0u8; size];
+    // SAFETY: `buffer` is `size` bytes long.
+    let read = unsafe {
+        libc::sysctl(
+            mib.as_mut_ptr(),
+            mib.len() as libc::c_uint,
+            buffer.as_mut_ptr().cast(),
+            &mut size,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if read != 0 {
+        return None;
+    }
+    buffer.truncate(size);
+    parse_process_arguments(&buffer)
+}
+
+/// `KERN_PROCARGS2` holds argc, the executable path padded with NULs, then
+/// the arguments; keep the first argc arguments.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_process_arguments(buffer: &[u8]) -> Option<Vec<u8>> {
+    let argc = i32::from_ne_bytes(buffer.get(..4)?.try_into().ok()?);
+    let rest = &buffer[4..];
+    let path_end = rest.iter().position(|&byte| byte == 0)?;
+    let arguments_start =
+        path_end + rest[path_end..].iter().position(|&byte| byte != 0)?;
+    let arguments = rest[arguments_start..]
+        .split(|&byte| byte == 0)
+        .take(usize::try_from(argc).ok()?)
+        .collect::<Vec<_>>()
+        .join(&0);
+    Some(arguments)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn foreground_process(_pid: u32) -> Option<String> {
+    None
+}
+
+/// Foreground process group of the controlling terminal, from the `tpgid`
+/// field of `/proc/<pid>/stat`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_foreground_group(stat: &str) -> Option<u32> {
+    // The command name may contain spaces and parentheses, so skip past the
+    // last ')'; tpgid is then the 6th field.
+    let fields = &stat[stat.rfind(')')? + 1..];
+    let tpgid: i64 = fields.split_whitespace().nth(5)?.parse().ok()?;
+    u32::try_from(tpgid).ok().filter(|&tpgid| tpgid > 0)
+}
+
+/// NUL-separated `/proc/<pid>/cmdline` as `name args...`, without the
+/// program's directory.
+#[cfg(any(target_os = "linux", target_os = "macos", test))]
+fn format_command_line(cmdline: &[u8]) -> String {
+    let text = String::from_utf8_lossy(cmdline);
+    let mut args = text.split('\0').filter(|arg| !arg.is_empty());
+    let Some(program) = args.next() else {
+        return String::new();
+    };
+    let program = program.rsplit('/').next().unwrap_or(program);
+
+    std::iter::once(program)
+        .chain(args)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Last `max_lines` lines of `term`; counts beyond the history are clamped
+/// before they become a signed line offset.
+fn last_lines_to_text<T>(term: &Term<T>, max_lines: usize) -> String {
+    let grid = term.grid();
+    let max_lines = min(max_lines, grid.total_lines());
+    if max_lines == 0 {
+        return String::new();
+    }
+
+    let bottom = grid.bottommost_line();
+    let start = bottom - (max_lines - 1);
+    let text = term.bounds_to_string(
+        Point::new(start, Column(0)),
+        Point::new(bottom, grid.last_column()),
+    );
+
+    text.trim_end().to_string()
+}
+
+/// Selected text the way alacritty copies it: soft-wrapped rows are joined,
+/// hard line breaks kept and trailing blanks trimmed.
+fn selection_to_text<T>(term: &Term<T>) -> String {
+    term.selection_to_string().unwrap_or_default()
+}
+
+/// Grid that fits `layout_size` with cells of whole pixels, `None` when not
+/// even one cell fits.
+fn terminal_size(layout_size: Size, font_size: Size) -> Option<TerminalSize> {
+    let cell_width = font_size.width.floor().max(1.0);
+    let cell_height = font_size.height.floor().max(1.0);
+    let num_lines = (layout_size.height / cell_height) as u16;
+    let num_cols = (layout_size.width / cell_width) as u16;
+    if num_lines == 0 || num_cols == 0 {
+        return None;
+    }
+
+    Some(TerminalSize {
+        cell_width: cell_width as u16,
+        cell_height: cell_height as u16,
+        num_cols,
+        num_lines,
+        layout_size,
+    })
+}
+
+/// Approximate bytes used by a grid's cells, ignoring per-cell extras
+/// such as zero-width characters and hyperlinks.
+fn grid_memory(grid: &Grid<Cell>) -> usize {
+    grid.total_lines() * grid.columns() * std::mem::size_of::<Cell>()
+}
+
+/// Count the matches in the newest `max_lines` lines and find the focused
+/// one among them.
+fn count_matches<T>(
+    term: &Term<T>,
+    regex: &mut RegexSearch,
+    focused: Option<&Match>,
+    max_lines: usize,
+) -> SearchStatus {
+    let bottom = term.bottommost_line();
+    let top = max(
+        term.topmost_line(),
+        bottom - max_lines.saturating_sub(1).min(i32::MAX as usize),
+    );
+    let start = Point::new(top, Column(0));
+    let end = Point::new(bottom, term.last_column());
+    let mut status = SearchStatus {
+        complete: top == term.topmost_line(),
+        ..SearchStatus::default()
+    };
+
+    for found in RegexIter::new(start, end, Direction::Right, term, regex) {
+        if status.total == MAX_COUNTED_MATCHES {
+            status.complete = false;
+            break;
+        }
+        status.total += 1;
+        if focused.is_some_and(|focused| focused.start() == found.start()) {
+            status.current = Some(status.total);
+        }
+    }
+    status
+}
+
 /// Copied from alacritty/src/display/hint.rs:
 /// Iterate over all visible regex matches.
 fn visible_regex_match_iter<'a>(
@@ -558,6 +1120,13 @@ pub struct RenderableContent {
     pub cursor: Cell,
     pub terminal_mode: TermMode,
     pub terminal_size: TerminalSize,
+    /// Search matches in and around the viewport
+    pub search_matches: Vec<Match>,
+    pub focused_search_match: Option<Match>,
+    /// Vi mode cursor, set while vi mode is active
+    pub vi_cursor: Option<Point>,
+    /// Colors the program changed with OSC 4/10/11/12
+    pub colors: Colors,
 }
 
 impl Default for RenderableContent {
@@ -569,6 +1138,10 @@ impl Default for RenderableContent {
             cursor: Cell::default(),
             terminal_mode: TermMode::empty(),
             terminal_size: TerminalSize::default(),
+            search_matches: Vec::new(),
+            focused_search_match: None,
+            vi_cursor: None,
+            colors: Colors::default(),
         }
     }
 }
@@ -587,3 +1160,221 @@ impl EventListener for EventProxy {
         let _ = self.0.send(event.clone());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::vte::ansi::Color;
+
+    #[test]
+    fn foreground_group_from_stat() {
+        let stat =
+            "4242 (cargo (build)) S 4100 4242 4100 34817 4242 4194304 0 0";
+        assert_eq!(parse_foreground_group(stat), Some(4242));
+
+        let detached = "4100 (zsh) S 1 4100 4100 0 -1 4194560 0 0";
+        assert_eq!(parse_foreground_group(detached), None);
+    }
+
+    #[test]
+    fn process_arguments_skip_executable_path() {
+        let mut buffer = 2i32.to_ne_bytes().to_vec();
+        buffer.extend_from_slice(
+            b"/usr/bin/vim\0\0\0vim\0notes.txt\0TERM=xterm\0",
+        );
+        assert_eq!(
+            parse_process_arguments(&buffer)
+                .map(|args| format_command_line(&args)),
+            Some("vim notes.txt".to_string())
+        );
+        assert_eq!(parse_process_arguments(b"\0\0"), None);
+    }
+
+    #[test]
+    fn grid_memory_grows_with_history() {
+        let mut grid: Grid<Cell> = Grid::new(24, 80, 1000);
+        let screen = grid_memory(&grid);
+        assert_eq!(screen, 24 * 80 * std::mem::size_of::<Cell>());
+
+        grid.scroll_up::<Color>(&(Line(0)..Line(24)), 10);
+        assert_eq!(
+            grid_memory(&grid),
+            screen + 10 * 80 * std::mem::size_of::<Cell>()
+        );
+    }
+
+    #[test]
+    fn terminal_size_reflows_with_cell_size() {
+        let layout = Size::new(800.0, 600.0);
+        let size = terminal_size(layout, Size::new(6.6, 13.4)).unwrap();
+        assert_eq!((size.cell_width, size.cell_height), (6, 13));
+        assert_eq!((size.num_cols, size.num_lines), (133, 46));
+
+        let zoomed = terminal_size(layout, Size::new(9.9, 20.2)).unwrap();
+        assert_eq!((zoomed.num_cols, zoomed.num_lines), (88, 30));
+
+        assert_eq!(
+            terminal_size(Size::new(5.0, 600.0), Size::new(6.6, 13.4)),
+            None
+        );
+    }
+
+    #[test]
+    fn selection_text_follows_line_breaks() {
+        let mut term = Term::new(
+            term::Config::default(),
+            &TermSize::new(10, 3),
+            alacritty_terminal::event::VoidListener,
+        );
+        let mut processor: Processor = Processor::new();
+        for byte in b"first\r\n0123456789abc  \r\nx\r\ny\r\nz" {
+            processor.advance(&mut term, *byte);
+        }
+        let point = |line, column| Point::new(Line(line), Column(column));
+        let select = |term: &mut Term<_>, ty, start, end| {
+            let mut selection = Selection::new(ty, start, Side::Left);
+            selection.update(end, Side::Right);
+            term.selection = Some(selection);
+        };
+
+        // "first" and the wrapped line have scrolled into history
+        select(&mut term, SelectionType::Simple, point(-3, 0), point(0, 0));
+        assert_eq!(selection_to_text(&term), "first\n0123456789abc\nx");
+
+        select(&mut term, SelectionType::Block, point(-3, 1), point(-2, 2));
+        assert_eq!(selection_to_text(&term), "ir\n12");
+
+        term.selection = None;
+        assert_eq!(selection_to_text(&term), "");
+    }
+
+    #[test]
+    fn last_lines_are_clamped_to_history() {
+        let mut term = Term::new(
+            term::Config::default(),
+            &TermSize::new(10, 3),
+            alacritty_terminal::event::VoidListener,
+        );
+        let mut processor: Processor = Processor::new();
+        for byte in b"one\r\ntwo\r\nthree\r\nfour" {
+            processor.advance(&mut term, *byte);
+        }
+
+        assert_eq!(last_lines_to_text(&term, 2), "three\nfour");
+        assert_eq!(last_lines_to_text(&term, 0), "");
+        assert_eq!(
+            last_lines_to_text(&term, usize::MAX),
+            "one\ntwo\nthree\nfour"
+        );
+    }
+
+    #[test]
+    fn search_steps_through_history() {
+        let mut term = Term::new(
+            term::Config::default(),
+            &TermSize::new(10, 3),
+            alacritty_terminal::event::VoidListener,
+        );
+        let mut processor: Processor = Processor::new();
+        for byte in b"Error one\r\nerror two\r\nterror\r\nERROR\r\nok\r\nend" {
+            processor.advance(&mut term, *byte);
+        }
+        let status = |current, total| SearchStatus {
+            current: Some(current),
+            total,
+            complete: true,
+        };
+        let start =
+            |search: &Search| search.focused.as_ref().map(|m| *m.start());
+
+        // The newest match is focused first, on screen
+        let mut search =
+            Search::new("error", SearchOptions::default()).unwrap();
+        search.step(&mut term, false, 0);
+        assert_eq!(search.status, status(4, 4));
+        assert_eq!(term.grid().display_offset(), 0);
+
+        // Stepping up scrolls each match into view
+        search.step(&mut term, false, 0);
+        assert_eq!(start(&search), Some(Point::new(Line(-1), Column(1))));
+        assert_eq!(term.grid().display_offset(), 1);
+        search.step(&mut term, false, 0);
+        search.step(&mut term, false, 0);
+        assert_eq!(search.status, status(1, 4));
+        assert_eq!(term.grid().display_offset(), 3);
+
+        // Both directions wrap around
+        search.step(&mut term, false, 0);
+        assert_eq!(search.status, status(4, 4));
+        search.step(&mut term, true, 0);
+        assert_eq!(search.status, status(1, 4));
+        assert_eq!(start(&search), Some(Point::new(Line(-3), Column(0))));
+        assert_eq!(term.grid().display_offset(), 3);
+
+        let mut total = |options| {
+            let mut search = Search::new("error", options).unwrap();
+            search.step(&mut term, false, 0);
+            search.status.total
+        };
+        let case_sensitive = SearchOptions {
+            case_sensitive: true,
+            ..SearchOptions::default()
+        };
+        let whole_word = SearchOptions {
+            whole_word: true,
+            ..SearchOptions::default()
+        };
+        assert_eq!(total(case_sensitive), 2);
+        assert_eq!(total(whole_word), 3);
+        assert_eq!(
+            total(SearchOptions {
+                case_sensitive: true,
+                whole_word: true,
+                regex: false,
+            }),
+            1
+        );
+    }
+
+    #[test]
+    fn search_counts_newest_lines_and_recounts_after_output() {
+        let mut term = Term::new(
+            term::Config::default(),
+            &TermSize::new(10, 3),
+            alacritty_terminal::event::VoidListener,
+        );
+        let mut processor: Processor = Processor::new();
+        for byte in b"Error one\r\nerror two\r\nterror\r\nERROR\r\nok\r\nend" {
+            processor.advance(&mut term, *byte);
+        }
+
+        // Only the screen fits in a three line window
+        let mut regex = RegexSearch::new("(?i)error").unwrap();
+        let window = count_matches(&term, &mut regex, None, 3);
+        assert_eq!((window.total, window.complete), (1, false));
+        let all = count_matches(&term, &mut regex, None, 100);
+        assert_eq!((all.total, all.complete), (4, true));
+
+        // Stepping keeps the count until the grid generation moves on
+        let mut search =
+            Search::new("error", SearchOptions::default()).unwrap();
+        search.step(&mut term, false, 0);
+        for byte in b"\r\nerror" {
+            processor.advance(&mut term, *byte);
+        }
+        search.step(&mut term, false, 0);
+        assert_eq!(search.status.total, 4);
+        search.step(&mut term, false, 1);
+        assert_eq!(search.status.total, 5);
+    }
+
+    #[test]
+    fn command_line_drops_program_directory() {
+        assert_eq!(
+            format_command_line(b"/usr/bin/vim\0notes.md\0"),
+            "vim notes.md"
+        );
+        assert_eq!(format_command_line(b"cargo\0build\0"), "cargo build");
+        assert_eq!(format_command_line(b""), "");
+    }
+}
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+/// How a scrollback search query is interpreted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Treat the query as a regular expression instead of plain text
+    pub regex: bool,
+    pub case_sensitive: bool,
+    /// Only match whole words
+    pub whole_word: bool,
+}
+
+/// Position of the focused match among all matches in the scrollback.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchStatus {
+    /// 1-based index of the focused match
+    pub current: Option<usize>,
+    pub total: usize,
+    /// False when counting stopped at `MAX_COUNTED_MATCHES` or the history
+    /// is longer than `MAX_COUNTED_LINES`
+    pub complete: bool,
+}
+
+/// Upper bound for counting matches, so huge histories stay responsive.
+pub(crate) const MAX_COUNTED_MATCHES: usize = 10_000;
+
+/// Only the newest lines are scanned for the match count.
+pub(crate) const MAX_COUNTED_LINES: usize = 10_000;
+
+/// How often the count is refreshed while new output arrives.
+pub(crate) const RECOUNT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Build the regex handed to alacritty's search for a query.
+pub(crate) fn search_pattern(query: &str, options: SearchOptions) -> String {
+    let body = if options.regex {
+        query.to_string()
+    } else {
+        escape(query)
+    };
+    // The lazy DFA only supports ASCII word boundaries
+    let body = if options.whole_word {
+        format!(r"(?-u:\b)(?:{})(?-u:\b)", body)
+    } else {
+        body
+    };
+    // Inline flags override alacritty's smart-case default
+    let flags = if options.case_sensitive {
+        "(?-i)"
+    } else {
+        "(?i)"
+    };
+
+    format!("{}{}", flags, body)
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$#&-~".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_text_is_escaped() {
+        let options = SearchOptions::default();
+        assert_eq!(search_pattern("a.b(c)", options), r"(?i)a\.b\(c\)");
+    }
+
+    #[test]
+    fn options_shape_the_pattern() {
+        let options = SearchOptions {
+            regex: true,
+            case_sensitive: true,
+            whole_word: true,
+        };
+        assert_eq!(
+            search_pattern("err(or)?", options),
+            r"(?-i)(?-u:\b)(?:err(or)?)(?-u:\b)"
+        );
+    }
+}
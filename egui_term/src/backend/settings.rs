@@ -2,6 +2,30 @@ use std::path::PathBuf;
 use std::collections::HashMap;
 
 const DEFAULT_SHELL: &str = "/bin/bash";
+const DEFAULT_SCROLLBACK_LINES: usize = 10_000;
+
+/// History size used for `ScrollbackLimit::Unlimited`, leaving headroom for
+/// alacritty adding the screen lines on top of it.
+const UNLIMITED_HISTORY: usize = usize::MAX / 2;
+
+/// How many lines a terminal keeps above its screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollbackLimit {
+    /// Keep at most this many lines, 0 disables scrollback.
+    Lines(usize),
+    /// Never drop old lines.
+    Unlimited,
+}
+
+impl ScrollbackLimit {
+    /// Line count handed to alacritty's grid.
+    pub fn history_size(self) -> usize {
+        match self {
+            ScrollbackLimit::Lines(lines) => lines,
+            ScrollbackLimit::Unlimited => UNLIMITED_HISTORY,
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct BackendSettings {
@@ -9,6 +33,7 @@ pub struct BackendSettings {
     pub args: Vec<String>,
     pub working_directory: Option<PathBuf>,
     pub env: HashMap<String, String>,
+    pub scrollback: ScrollbackLimit,
 }
 
 impl Default for BackendSettings {
@@ -25,6 +50,19 @@ impl Default for BackendSettings {
             args: vec![],
             working_directory: None,
             env,
+            scrollback: ScrollbackLimit::Lines(DEFAULT_SCROLLBACK_LINES),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_size_from_limit() {
+        assert_eq!(ScrollbackLimit::Lines(0).history_size(), 0);
+        assert_eq!(ScrollbackLimit::Lines(5000).history_size(), 5000);
+        assert!(ScrollbackLimit::Unlimited.history_size() > usize::MAX / 4);
+    }
+}
@@ -77,9 +77,11 @@ macro_rules! generate_bindings {
     }};
 }
 
+/// Ordered binding table; `A` is the action type, so applications can reuse
+/// the same lookup rules for their own actions.
 #[derive(Clone, Debug)]
-pub struct BindingsLayout {
-    layout: Vec<(Binding<InputKind>, BindingAction)>,
+pub struct BindingsLayout<A = BindingAction> {
+    layout: Vec<(Binding<InputKind>, A)>,
 }
 
 impl Default for BindingsLayout {
@@ -98,10 +100,24 @@ impl BindingsLayout {
         layout
     }
 
-    pub fn add_bindings(
-        &mut self,
-        bindings: Vec<(Binding<InputKind>, BindingAction)>,
-    ) {
+    pub fn get_action(
+        &self,
+        input: InputKind,
+        modifiers: Modifiers,
+        terminal_mode: TerminalMode,
+    ) -> BindingAction {
+        self.find_action(&input, modifiers, terminal_mode)
+            .cloned()
+            .unwrap_or(BindingAction::Ignore)
+    }
+}
+
+impl<A> BindingsLayout<A> {
+    pub fn empty() -> Self {
+        Self { layout: Vec::new() }
+    }
+
+    pub fn add_bindings(&mut self, bindings: Vec<(Binding<InputKind>, A)>) {
         for (binding, action) in bindings {
             match self
                 .layout
@@ -114,24 +130,25 @@ impl BindingsLayout {
         }
     }
 
-    pub fn get_action(
+    pub fn find_action(
         &self,
-        input: InputKind,
+        input: &InputKind,
         modifiers: Modifiers,
         terminal_mode: TerminalMode,
-    ) -> BindingAction {
-        for (binding, action) in &self.layout {
-            let is_triggered = binding.target == input
-                && modifiers.matches_exact(binding.modifiers)
-                && terminal_mode.contains(binding.terminal_mode_include)
-                && !terminal_mode.intersects(binding.terminal_mode_exclude);
-
-            if is_triggered {
-                return action.clone();
-            };
-        }
+    ) -> Option<&A> {
+        self.layout
+            .iter()
+            .find(|(binding, _)| {
+                binding.target == *input
+                    && modifiers.matches_exact(binding.modifiers)
+                    && terminal_mode.contains(binding.terminal_mode_include)
+                    && !terminal_mode.intersects(binding.terminal_mode_exclude)
+            })
+            .map(|(_, action)| action)
+    }
 
-        BindingAction::Ignore
+    pub fn bindings(&self) -> &[(Binding<InputKind>, A)] {
+        &self.layout
     }
 }
 
@@ -223,7 +240,7 @@ fn default_keyboard_bindings() -> Vec<(Binding<InputKind>, BindingAction)> {
         R,            Modifiers::CTRL; BindingAction::Char('\x12');
         S,            Modifiers::CTRL; BindingAction::Char('\x13');
         T,            Modifiers::CTRL; BindingAction::Char('\x14');
-        U,            Modifiers::CTRL; BindingAction::Char('\x51');
+        U,            Modifiers::CTRL; BindingAction::Char('\x15');
         V,            Modifiers::CTRL; BindingAction::Char('\x16');
         W,            Modifiers::CTRL; BindingAction::Char('\x17');
         X,            Modifiers::CTRL; BindingAction::Char('\x18');
@@ -291,7 +308,7 @@ fn default_keyboard_bindings() -> Vec<(Binding<InputKind>, BindingAction)> {
         R,        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x12');
         S,        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x13');
         T,        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x14');
-        U,        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x51');
+        U,        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x15');
         V,        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x16');
         W,        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x17');
         X,        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x18');
@@ -467,6 +484,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn find_custom_action() {
+        let mut current_layout = BindingsLayout::empty();
+        current_layout.add_bindings(generate_bindings!(
+            KeyboardBinding;
+            T, Modifiers::COMMAND; 1u8;
+            T, Modifiers::COMMAND | Modifiers::SHIFT; 2u8;
+        ));
+        let find = |modifiers| {
+            current_layout
+                .find_action(
+                    &InputKind::KeyCode(Key::T),
+                    modifiers,
+                    TerminalMode::empty(),
+                )
+                .copied()
+        };
+        assert_eq!(find(Modifiers::COMMAND), Some(1));
+        assert_eq!(find(Modifiers::COMMAND | Modifiers::SHIFT), Some(2));
+        assert_eq!(find(Modifiers::ALT), None);
+    }
+
     #[test]
     fn get_action_with_custom_bindings() {
         let mut current_layout = BindingsLayout::default();
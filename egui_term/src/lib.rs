@@ -5,9 +5,16 @@ mod theme;
 mod types;
 mod view;
 
-pub use backend::settings::BackendSettings;
-pub use backend::{BackendCommand, PtyEvent, TerminalBackend, TerminalMode};
-pub use bindings::{Binding, BindingAction, InputKind, KeyboardBinding};
+pub use backend::export::{ExportFormat, HistorySnapshot};
+pub use backend::search::{SearchOptions, SearchStatus};
+pub use backend::settings::{BackendSettings, ScrollbackLimit};
+pub use backend::{
+    BackendCommand, PtyEvent, SelectionType, TerminalBackend, TerminalMode,
+    ViMotion,
+};
+pub use bindings::{
+    Binding, BindingAction, BindingsLayout, InputKind, KeyboardBinding,
+};
 pub use font::{FontSettings, TerminalFont};
 pub use theme::{ColorPalette, TerminalTheme};
 pub use view::TerminalView;
@@ -1,4 +1,5 @@
-use alacritty_terminal::vte::ansi::{self, NamedColor};
+use alacritty_terminal::term::color::Colors;
+use alacritty_terminal::vte::ansi::{self, NamedColor, Rgb};
 use egui::Color32;
 use std::collections::HashMap;
 
@@ -203,8 +204,73 @@ impl TerminalTheme {
             },
         }
     }
+
+    /// Color as drawn in a terminal whose program changed some colors with
+    /// OSC 4/10/11/12; colors it did not change come from the palette.
+    pub fn get_color_with_overrides(
+        &self,
+        c: ansi::Color,
+        overrides: &Colors,
+    ) -> Color32 {
+        let index = match c {
+            ansi::Color::Named(named) => named as usize,
+            ansi::Color::Indexed(index) => index as usize,
+            ansi::Color::Spec(_) => return self.get_color(c),
+        };
+        match overrides[index] {
+            Some(rgb) => Color32::from_rgb(rgb.r, rgb.g, rgb.b),
+            None => self.get_color(c),
+        }
+    }
+
+    /// Color reported for an OSC color query, `index` being alacritty's
+    /// color index: 0..256 for the palette, then foreground, background,
+    /// cursor and the dim and bright variants.
+    pub fn get_indexed_color(&self, index: usize, overrides: &Colors) -> Rgb {
+        let color = match index {
+            0..=255 => self.get_color_with_overrides(
+                ansi::Color::Indexed(index as u8),
+                overrides,
+            ),
+            _ => {
+                let named = NAMED_COLORS
+                    .get(index - NamedColor::Foreground as usize)
+                    .copied()
+                    .unwrap_or(NamedColor::Foreground);
+                match (named, overrides[index]) {
+                    (_, Some(rgb)) => Color32::from_rgb(rgb.r, rgb.g, rgb.b),
+                    // The cursor is drawn in the text color unless set
+                    (NamedColor::Cursor, None) => self
+                        .get_color(ansi::Color::Named(NamedColor::Foreground)),
+                    (named, None) => self.get_color(ansi::Color::Named(named)),
+                }
+            },
+        };
+        Rgb {
+            r: color.r(),
+            g: color.g(),
+            b: color.b(),
+        }
+    }
 }
 
+/// Named colors in index order, starting at `NamedColor::Foreground`.
+const NAMED_COLORS: [NamedColor; 13] = [
+    NamedColor::Foreground,
+    NamedColor::Background,
+    NamedColor::Cursor,
+    NamedColor::DimBlack,
+    NamedColor::DimRed,
+    NamedColor::DimGreen,
+    NamedColor::DimYellow,
+    NamedColor::DimBlue,
+    NamedColor::DimMagenta,
+    NamedColor::DimCyan,
+    NamedColor::DimWhite,
+    NamedColor::BrightForeground,
+    NamedColor::DimForeground,
+];
+
 fn hex_to_color(hex: &str) -> anyhow::Result<Color32> {
     if hex.len() != 7 {
         return Err(anyhow::format_err!("input string is in non valid format"));
@@ -216,3 +282,73 @@ fn hex_to_color(hex: &str) -> anyhow::Result<Color32> {
 
     Ok(Color32::from_rgb(r, g, b))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_take_precedence_over_palette() {
+        let theme = TerminalTheme::default();
+        let mut overrides = Colors::default();
+        let background = ansi::Color::Named(NamedColor::Background);
+        assert_eq!(
+            theme.get_color_with_overrides(background, &overrides),
+            Color32::from_rgb(0x18, 0x18, 0x18)
+        );
+
+        let rgb = Rgb {
+            r: 0xfd,
+            g: 0xf6,
+            b: 0xe3,
+        };
+        overrides[NamedColor::Background] = Some(rgb);
+        overrides[1] = Some(rgb);
+        assert_eq!(
+            theme.get_color_with_overrides(background, &overrides),
+            Color32::from_rgb(0xfd, 0xf6, 0xe3)
+        );
+        assert_eq!(
+            theme.get_color_with_overrides(
+                ansi::Color::Named(NamedColor::Red),
+                &overrides
+            ),
+            Color32::from_rgb(0xfd, 0xf6, 0xe3)
+        );
+        assert_eq!(theme.get_indexed_color(257, &overrides), rgb);
+    }
+
+    #[test]
+    fn indexed_colors_follow_the_palette() {
+        let theme = TerminalTheme::default();
+        let colors = Colors::default();
+        let red = Rgb {
+            r: 0xac,
+            g: 0x42,
+            b: 0x42,
+        };
+        assert_eq!(theme.get_indexed_color(1, &colors), red);
+        assert_eq!(
+            theme.get_indexed_color(NamedColor::DimRed as usize, &colors),
+            Rgb {
+                r: 0x71,
+                g: 0x2b,
+                b: 0x2b
+            }
+        );
+        let foreground = Rgb {
+            r: 0xd8,
+            g: 0xd8,
+            b: 0xd8,
+        };
+        assert_eq!(theme.get_indexed_color(256, &colors), foreground);
+        assert_eq!(
+            theme.get_indexed_color(NamedColor::Cursor as usize, &colors),
+            foreground
+        );
+        assert_eq!(
+            theme.get_indexed_color(16, &colors),
+            Rgb { r: 0, g: 0, b: 0 }
+        );
+    }
+}
@@ -22,6 +22,18 @@ use crate::types::Size;
 
 const EGUI_TERM_WIDGET_ID_PREFIX: &str = "egui_term::instance::";
 
+const SEARCH_MATCH_BG: egui::Color32 =
+    egui::Color32::from_rgb(0xac, 0x8e, 0x3b);
+const FOCUSED_SEARCH_MATCH_BG: egui::Color32 =
+    egui::Color32::from_rgb(0xf0, 0xa0, 0x30);
+/// Lines per second a selection dragged just past the view scrolls by.
+const SELECTION_SCROLL_MIN_SPEED: f32 = 5.0;
+/// Extra lines per second for every pixel the pointer is past the view.
+const SELECTION_SCROLL_ACCELERATION: f32 = 0.5;
+
+const SEARCH_MATCH_FG: egui::Color32 =
+    egui::Color32::from_rgb(0x1e, 0x1e, 0x1e);
+
 #[derive(Debug, Clone)]
 enum InputAction {
     BackendCall(BackendCommand),
@@ -33,6 +45,7 @@ enum InputAction {
 pub struct TerminalViewState {
     is_dragged: bool,
     scroll_pixels: f32,
+    selection_scroll_lines: f32,
     current_mouse_position_on_grid: TerminalGridPoint,
 }
 
@@ -44,6 +57,7 @@ pub struct TerminalView<'a> {
     font: TerminalFont,
     theme: TerminalTheme,
     bindings_layout: BindingsLayout,
+    block_selection_modifiers: Modifiers,
 }
 
 impl Widget for TerminalView<'_> {
@@ -61,6 +75,7 @@ impl Widget for TerminalView<'_> {
         self.focus(&layout)
             .resize(&layout)
             .process_input(&layout, &mut state)
+            .scroll_selection(&layout, &mut state)
             .show(&mut state, &layout, &painter);
 
         ui.memory_mut(|m| m.data.insert_temp(widget_id, state));
@@ -83,6 +98,7 @@ impl<'a> TerminalView<'a> {
             font: TerminalFont::default(),
             theme: TerminalTheme::default(),
             bindings_layout: BindingsLayout::new(),
+            block_selection_modifiers: Modifiers::ALT,
         }
     }
 
@@ -110,6 +126,16 @@ impl<'a> TerminalView<'a> {
         self
     }
 
+    /// Modifiers that make a left-button drag select a rectangle.
+    #[inline]
+    pub fn set_block_selection_modifiers(
+        mut self,
+        modifiers: Modifiers,
+    ) -> Self {
+        self.block_selection_modifiers = modifiers;
+        self
+    }
+
     #[inline]
     pub fn add_bindings(
         mut self,
@@ -143,7 +169,12 @@ impl<'a> TerminalView<'a> {
         layout: &Response,
         state: &mut TerminalViewState,
     ) -> Self {
-        if !layout.has_focus() || !layout.contains_pointer() {
+        // Keys need keyboard focus; pointer input needs the pointer over the
+        // view, or a selection drag that started in it. Without keyboard
+        // focus the pointer only selects: no wheel, mouse reports or links
+        let has_keyboard = layout.has_focus();
+        let has_pointer = layout.contains_pointer() || state.is_dragged;
+        if !has_keyboard && !has_pointer {
             return self;
         }
 
@@ -156,7 +187,9 @@ impl<'a> TerminalView<'a> {
                 egui::Event::Text(_)
                 | egui::Event::Key { .. }
                 | egui::Event::Copy
-                | egui::Event::Paste(_) => {
+                | egui::Event::Paste(_)
+                    if has_keyboard =>
+                {
                     input_actions.push(process_keyboard_event(
                         event,
                         self.backend,
@@ -164,24 +197,43 @@ impl<'a> TerminalView<'a> {
                         modifiers,
                     ))
                 },
-                egui::Event::MouseWheel { unit, delta, .. } => input_actions
-                    .push(process_mouse_wheel(
+                _ if !has_pointer => {},
+                egui::Event::MouseWheel { unit, delta, .. } if has_keyboard => {
+                    input_actions.push(process_mouse_wheel(
                         state,
                         self.font.font_type().size,
                         unit,
                         delta,
-                    )),
+                    ))
+                },
+                egui::Event::PointerButton {
+                    button: PointerButton::Primary,
+                    pressed,
+                    modifiers,
+                    pos,
+                    ..
+                } if !has_keyboard => {
+                    input_actions.push(process_selection_click(
+                        state,
+                        layout,
+                        self.block_selection_modifiers,
+                        pos,
+                        &modifiers,
+                        pressed,
+                    ))
+                },
                 egui::Event::PointerButton {
                     button,
                     pressed,
                     modifiers,
                     pos,
                     ..
-                } => input_actions.push(process_button_click(
+                } if has_keyboard => input_actions.push(process_button_click(
                     state,
                     layout,
                     self.backend,
                     &self.bindings_layout,
+                    self.block_selection_modifiers,
                     button,
                     pos,
                     &modifiers,
@@ -194,6 +246,7 @@ impl<'a> TerminalView<'a> {
                         self.backend,
                         pos,
                         &modifiers,
+                        has_keyboard,
                     )
                 },
                 _ => {},
@@ -215,6 +268,48 @@ impl<'a> TerminalView<'a> {
         self
     }
 
+    /// Keep scrolling while a selection is dragged above or below the view,
+    /// faster the further away the pointer is.
+    fn scroll_selection(
+        self,
+        layout: &Response,
+        state: &mut TerminalViewState,
+    ) -> Self {
+        let (position, dt) =
+            layout.ctx.input(|i| (i.pointer.latest_pos(), i.stable_dt));
+        let rect = layout.rect;
+        // Positive above the view, negative below it
+        let distance = match position.filter(|_| state.is_dragged) {
+            Some(position) if position.y < rect.top() => {
+                rect.top() - position.y
+            },
+            Some(position) if position.y > rect.bottom() => {
+                rect.bottom() - position.y
+            },
+            _ => 0.0,
+        };
+        let Some(position) = position.filter(|_| distance != 0.0) else {
+            state.selection_scroll_lines = 0.0;
+            return self;
+        };
+
+        let speed = SELECTION_SCROLL_MIN_SPEED
+            + distance.abs() * SELECTION_SCROLL_ACCELERATION;
+        state.selection_scroll_lines += distance.signum() * speed * dt.min(0.1);
+        let lines = state.selection_scroll_lines.trunc();
+        state.selection_scroll_lines -= lines;
+        if lines != 0.0 {
+            self.backend.process_command(BackendCommand::SelectScroll(
+                lines as i32,
+                position.x - rect.min.x,
+                position.y - rect.min.y,
+            ));
+        }
+
+        layout.ctx.request_repaint();
+        self
+    }
+
     fn show(
         self,
         state: &mut TerminalViewState,
@@ -226,8 +321,11 @@ impl<'a> TerminalView<'a> {
         let layout_max = layout.rect.max;
         let cell_height = content.terminal_size.cell_height as f32;
         let cell_width = content.terminal_size.cell_width as f32;
-        let global_bg =
-            self.theme.get_color(Color::Named(NamedColor::Background));
+        let colors = &content.colors;
+        let global_bg = self.theme.get_color_with_overrides(
+            Color::Named(NamedColor::Background),
+            colors,
+        );
 
         let mut shapes = vec![Shape::Rect(RectShape::filled(
             Rect::from_min_max(layout_min, layout_max),
@@ -252,6 +350,15 @@ impl<'a> TerminalView<'a> {
             let is_selected = content
                 .selectable_range
                 .is_some_and(|r| r.contains(indexed.point));
+            let is_focused_search_match = content
+                .focused_search_match
+                .as_ref()
+                .is_some_and(|m| m.contains(&indexed.point));
+            let is_search_match = is_focused_search_match
+                || content
+                    .search_matches
+                    .iter()
+                    .any(|m| m.contains(&indexed.point));
             let is_hovered_hyperling =
                 content.hovered_hyperlink.as_ref().is_some_and(|r| {
                     r.contains(&indexed.point)
@@ -263,8 +370,10 @@ impl<'a> TerminalView<'a> {
                 indexed.point.line.0 + content.grid.display_offset() as i32;
             let y = layout_min.y + (cell_height * line_num as f32);
 
-            let mut fg = self.theme.get_color(indexed.fg);
-            let mut bg = self.theme.get_color(indexed.bg);
+            let mut fg =
+                self.theme.get_color_with_overrides(indexed.fg, colors);
+            let mut bg =
+                self.theme.get_color_with_overrides(indexed.bg, colors);
             let cell_width = if is_wide_char {
                 cell_width * 2.0
             } else {
@@ -279,6 +388,15 @@ impl<'a> TerminalView<'a> {
                 std::mem::swap(&mut fg, &mut bg);
             }
 
+            if is_search_match && !is_selected {
+                fg = SEARCH_MATCH_FG;
+                bg = if is_focused_search_match {
+                    FOCUSED_SEARCH_MATCH_BG
+                } else {
+                    SEARCH_MATCH_BG
+                };
+            }
+
             if global_bg != bg {
                 shapes.push(Shape::Rect(RectShape::filled(
                     Rect::from_min_size(
@@ -304,8 +422,15 @@ impl<'a> TerminalView<'a> {
             }
 
             // Handle cursor rendering
-            if content.grid.cursor.point == indexed.point {
-                let cursor_color = self.theme.get_color(content.cursor.fg);
+            let cursor_point =
+                content.vi_cursor.unwrap_or(content.grid.cursor.point);
+            if cursor_point == indexed.point {
+                let cursor_color = match colors[NamedColor::Cursor] {
+                    Some(rgb) => egui::Color32::from_rgb(rgb.r, rgb.g, rgb.b),
+                    None => self
+                        .theme
+                        .get_color_with_overrides(content.cursor.fg, colors),
+                };
                 shapes.push(Shape::Rect(RectShape::filled(
                     Rect::from_min_size(
                         Pos2::new(x, y),
@@ -318,8 +443,8 @@ impl<'a> TerminalView<'a> {
 
             // Draw text content
             if indexed.c != ' ' && indexed.c != '\t' {
-                if content.grid.cursor.point == indexed.point
-                    && is_app_cursor_mode
+                if cursor_point == indexed.point
+                    && (is_app_cursor_mode || content.vi_cursor.is_some())
                 {
                     std::mem::swap(&mut fg, &mut bg);
                 }
@@ -485,6 +610,7 @@ fn process_button_click(
     layout: &Response,
     backend: &TerminalBackend,
     bindings_layout: &BindingsLayout,
+    block_selection_modifiers: Modifiers,
     button: PointerButton,
     position: Pos2,
     modifiers: &Modifiers,
@@ -496,6 +622,7 @@ fn process_button_click(
             layout,
             backend,
             bindings_layout,
+            block_selection_modifiers,
             position,
             modifiers,
             pressed,
@@ -509,6 +636,7 @@ fn process_left_button(
     layout: &Response,
     backend: &TerminalBackend,
     bindings_layout: &BindingsLayout,
+    block_selection_modifiers: Modifiers,
     position: Pos2,
     modifiers: &Modifiers,
     pressed: bool,
@@ -522,7 +650,8 @@ fn process_left_button(
             pressed,
         ))
     } else if pressed {
-        process_left_button_pressed(state, layout, position)
+        let is_block = is_block_selection(block_selection_modifiers, modifiers);
+        process_left_button_pressed(state, layout, position, is_block)
     } else {
         process_left_button_released(
             state,
@@ -535,13 +664,49 @@ fn process_left_button(
     }
 }
 
+/// Left button on a view without keyboard focus: selects even in mouse
+/// mode, never reports to the PTY or opens links.
+fn process_selection_click(
+    state: &mut TerminalViewState,
+    layout: &Response,
+    block_selection_modifiers: Modifiers,
+    position: Pos2,
+    modifiers: &Modifiers,
+    pressed: bool,
+) -> InputAction {
+    if pressed {
+        let is_block = is_block_selection(block_selection_modifiers, modifiers);
+        process_left_button_pressed(state, layout, position, is_block)
+    } else {
+        state.is_dragged = false;
+        if layout.double_clicked() || layout.triple_clicked() {
+            InputAction::BackendCall(build_start_select_command(
+                layout, position, false,
+            ))
+        } else {
+            InputAction::Ignore
+        }
+    }
+}
+
+fn is_block_selection(
+    block_selection_modifiers: Modifiers,
+    modifiers: &Modifiers,
+) -> bool {
+    !block_selection_modifiers.is_none()
+        && modifiers.contains(block_selection_modifiers)
+}
+
 fn process_left_button_pressed(
     state: &mut TerminalViewState,
     layout: &Response,
     position: Pos2,
+    is_block: bool,
 ) -> InputAction {
     state.is_dragged = true;
-    InputAction::BackendCall(build_start_select_command(layout, position))
+    InputAction::BackendCall(build_start_select_command(
+        layout, position, is_block,
+    ))
 }
 
 fn process_left_button_released(
@@ -554,7 +719,9 @@ fn process_left_button_released(
 ) -> InputAction {
     state.is_dragged = false;
     if layout.double_clicked() || layout.triple_clicked() {
-        InputAction::BackendCall(build_start_select_command(layout, position))
+        InputAction::BackendCall(build_start_select_command(
+            layout, position, false,
+        ))
     } else {
         let terminal_content = backend.last_content();
         let binding_action = bindings_layout.get_action(
@@ -577,8 +744,11 @@ fn process_left_button_released(
 fn build_start_select_command(
     layout: &Response,
     cursor_position: Pos2,
+    is_block: bool,
 ) -> BackendCommand {
-    let selection_type = if layout.double_clicked() {
+    let selection_type = if is_block {
+        SelectionType::Block
+    } else if layout.double_clicked() {
         SelectionType::Semantic
     } else if layout.triple_clicked() {
         SelectionType::Lines
@@ -599,6 +769,7 @@ fn process_mouse_move(
     backend: &TerminalBackend,
     position: Pos2,
     modifiers: &Modifiers,
+    has_keyboard: bool,
 ) -> Vec<InputAction> {
     let terminal_content = backend.last_content();
     let cursor_x = position.x - layout.rect.min.x;
@@ -614,7 +785,8 @@ fn process_mouse_move(
     // Handle command or selection update based on terminal mode and modifiers
     if state.is_dragged {
         let terminal_mode = terminal_content.terminal_mode;
-        let cmd = if terminal_mode.contains(TermMode::MOUSE_MOTION)
+        let cmd = if has_keyboard
+            && terminal_mode.contains(TermMode::MOUSE_MOTION)
             && modifiers.is_none()
         {
             InputAction::BackendCall(BackendCommand::MouseReport(
@@ -633,7 +805,7 @@ fn process_mouse_move(
     }
 
     // Handle link hover if applicable
-    if modifiers.command_only() {
+    if has_keyboard && modifiers.command_only() {
         actions.push(InputAction::BackendCall(BackendCommand::ProcessLink(
             LinkAction::Hover,
             state.current_mouse_position_on_grid,
@@ -0,0 +1,275 @@
+use crate::types::{AppState, CloseTarget, FocusDirection, PendingClose, SplitDirection};
+use crate::tab_manager::TabManager;
+use crate::split_manager::SplitManager;
+use crate::grid_manager::GridManager;
+use crate::broadcast_manager::BroadcastManager;
+use crate::search_manager::SearchManager;
+use crate::export_manager::ExportManager;
+use crate::copy_mode_manager::CopyModeManager;
+use crate::palette_manager::PaletteManager;
+use crate::font_manager::FontManager;
+use crate::theme_manager::ThemeManager;
+
+/// Application-level actions that can be bound to keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppAction {
+    NewTab,
+    CloseTab,
+    SplitHorizontal,
+    SplitVertical,
+    ToggleGrid,
+    ToggleBroadcast,
+    ToggleAllBroadcast,
+    FocusNextPane,
+    FocusPane(FocusDirection),
+    ResizePane(FocusDirection),
+    EqualizePanes,
+    ClosePane,
+    ToggleZoom,
+    SwapPane(FocusDirection),
+    RotateSplit,
+    FlipSplit,
+    BreakPane,
+    RenameTab,
+    NextTab,
+    PreviousTab,
+    MoveTabLeft,
+    MoveTabRight,
+    Find,
+    SaveOutput,
+    CopyMode,
+    ClearScrollback,
+    TogglePaneBroadcast,
+    DuplicateTab,
+    CloseOtherTabs,
+    CommandPalette,
+    IncreaseFontSize,
+    DecreaseFontSize,
+    ResetFontSize,
+    IncreaseGlobalFontSize,
+    DecreaseGlobalFontSize,
+    ResetGlobalFontSize,
+    SelectTheme,
+    JoinTab(usize), // 0-based tab index
+    SwitchToTab(usize), // 0-based tab index
+}
+
+/// Config names of the actions without parameters
+pub const NAMED_ACTIONS: &[(&str, AppAction)] = &[
+    ("new_tab", AppAction::NewTab),
+    ("close_tab", AppAction::CloseTab),
+    ("split_horizontal", AppAction::SplitHorizontal),
+    ("split_vertical", AppAction::SplitVertical),
+    ("toggle_grid", AppAction::ToggleGrid),
+    ("toggle_broadcast", AppAction::ToggleBroadcast),
+    ("toggle_all_broadcast", AppAction::ToggleAllBroadcast),
+    ("focus_next_pane", AppAction::FocusNextPane),
+    ("focus_pane_left", AppAction::FocusPane(FocusDirection::Left)),
+    ("focus_pane_right", AppAction::FocusPane(FocusDirection::Right)),
+    ("focus_pane_up", AppAction::FocusPane(FocusDirection::Up)),
+    ("focus_pane_down", AppAction::FocusPane(FocusDirection::Down)),
+    ("resize_pane_left", AppAction::ResizePane(FocusDirection::Left)),
+    ("resize_pane_right", AppAction::ResizePane(FocusDirection::Right)),
+    ("resize_pane_up", AppAction::ResizePane(FocusDirection::Up)),
+    ("resize_pane_down", AppAction::ResizePane(FocusDirection::Down)),
+    ("equalize_panes", AppAction::EqualizePanes),
+    ("close_pane", AppAction::ClosePane),
+    ("toggle_zoom", AppAction::ToggleZoom),
+    ("swap_pane_left", AppAction::SwapPane(FocusDirection::Left)),
+    ("swap_pane_right", AppAction::SwapPane(FocusDirection::Right)),
+    ("swap_pane_up", AppAction::SwapPane(FocusDirection::Up)),
+    ("swap_pane_down", AppAction::SwapPane(FocusDirection::Down)),
+    ("rotate_split", AppAction::RotateSplit),
+    ("flip_split", AppAction::FlipSplit),
+    ("break_pane", AppAction::BreakPane),
+    ("rename_tab", AppAction::RenameTab),
+    ("next_tab", AppAction::NextTab),
+    ("previous_tab", AppAction::PreviousTab),
+    ("move_tab_left", AppAction::MoveTabLeft),
+    ("move_tab_right", AppAction::MoveTabRight),
+    ("find", AppAction::Find),
+    ("save_output", AppAction::SaveOutput),
+    ("copy_mode", AppAction::CopyMode),
+    ("clear_scrollback", AppAction::ClearScrollback),
+    ("toggle_pane_broadcast", AppAction::TogglePaneBroadcast),
+    ("duplicate_tab", AppAction::DuplicateTab),
+    ("close_other_tabs", AppAction::CloseOtherTabs),
+    ("command_palette", AppAction::CommandPalette),
+    ("increase_font_size", AppAction::IncreaseFontSize),
+    ("decrease_font_size", AppAction::DecreaseFontSize),
+    ("reset_font_size", AppAction::ResetFontSize),
+    ("increase_global_font_size", AppAction::IncreaseGlobalFontSize),
+    ("decrease_global_font_size", AppAction::DecreaseGlobalFontSize),
+    ("reset_global_font_size", AppAction::ResetGlobalFontSize),
+    ("select_theme", AppAction::SelectTheme),
+];
+
+/// Prefix of `switch_to_tab_1` .. `switch_to_tab_9`
+const SWITCH_TO_TAB_PREFIX: &str = "switch_to_tab_";
+
+/// Prefix of `join_tab_1` .. `join_tab_9`
+const JOIN_TAB_PREFIX: &str = "join_tab_";
+
+impl AppAction {
+    /// Parse a `[keybindings]` action name
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some(number) = name.strip_prefix(SWITCH_TO_TAB_PREFIX) {
+            return Self::tab_index(number).map(AppAction::SwitchToTab);
+        }
+        if let Some(number) = name.strip_prefix(JOIN_TAB_PREFIX) {
+            return Self::tab_index(number).map(AppAction::JoinTab);
+        }
+        NAMED_ACTIONS.iter()
+            .find(|(named, _)| *named == name)
+            .map(|(_, action)| *action)
+    }
+    
+    /// 1-based tab number from an action name, as a 0-based index
+    fn tab_index(number: &str) -> Option<usize> {
+        match number.parse::<usize>() {
+            Ok(n @ 1..=9) => Some(n - 1),
+            _ => None,
+        }
+    }
+}
+
+pub struct ActionManager;
+
+impl ActionManager {
+    /// Run an application action
+    pub fn dispatch(state: &mut AppState, action: AppAction) {
+        match action {
+            AppAction::NewTab => TabManager::create_new_tab(state),
+            AppAction::CloseTab => {
+                if state.tabs.len() > 1 {
+                    Self::request_close(state, CloseTarget::Tab(state.active_tab_id));
+                }
+            }
+            AppAction::SplitHorizontal => SplitManager::split_focused_panel(state, SplitDirection::Horizontal),
+            AppAction::SplitVertical => SplitManager::split_focused_panel(state, SplitDirection::Vertical),
+            AppAction::ToggleGrid => GridManager::toggle_grid_view(state),
+            AppAction::ToggleBroadcast => BroadcastManager::toggle_broadcast_mode(state),
+            AppAction::ToggleAllBroadcast => BroadcastManager::toggle_all_terminals_selection(state),
+            AppAction::FocusNextPane => SplitManager::navigate_focus_in_splits(state),
+            AppAction::FocusPane(direction) => SplitManager::focus_pane_in_direction(state, direction),
+            AppAction::ResizePane(direction) => SplitManager::resize_focused_pane(state, direction),
+            AppAction::EqualizePanes => SplitManager::equalize_splits(state),
+            AppAction::ClosePane => {
+                if let Some(terminal_id) = state.focused_terminal {
+                    Self::request_close(state, CloseTarget::Pane(terminal_id));
+                }
+            }
+            AppAction::ToggleZoom => SplitManager::toggle_zoom(state),
+            AppAction::SwapPane(direction) => SplitManager::swap_focused_pane(state, direction),
+            AppAction::RotateSplit => SplitManager::rotate_focused_split(state),
+            AppAction::FlipSplit => SplitManager::flip_focused_split(state),
+            AppAction::BreakPane => {
+                if let Some(terminal_id) = state.focused_terminal {
+                    TabManager::break_pane_to_tab(state, terminal_id);
+                }
+            }
+            AppAction::RenameTab => TabManager::start_rename(state, state.active_tab_id),
+            AppAction::NextTab => TabManager::switch_relative(state, 1),
+            AppAction::PreviousTab => TabManager::switch_relative(state, -1),
+            AppAction::MoveTabLeft => TabManager::move_tab_by(state, state.active_tab_id, -1),
+            AppAction::MoveTabRight => TabManager::move_tab_by(state, state.active_tab_id, 1),
+            AppAction::Find => SearchManager::open_find_bar(state),
+            AppAction::SaveOutput => {
+                if let Some(terminal_id) = state.focused_terminal {
+                    ExportManager::open_dialog(state, terminal_id);
+                }
+            }
+            AppAction::CopyMode => CopyModeManager::toggle(state),
+            AppAction::ClearScrollback => {
+                if let Some(terminal) = state.focused_terminal.and_then(|id| state.terminals.get_mut(&id)) {
+                    terminal.clear_history();
+                }
+            }
+            AppAction::TogglePaneBroadcast => {
+                if let Some(terminal_id) = state.focused_terminal {
+                    BroadcastManager::toggle_pane_broadcast(state, terminal_id);
+                }
+            }
+            AppAction::DuplicateTab => TabManager::duplicate_tab(state, state.active_tab_id),
+            AppAction::CloseOtherTabs => Self::request_close(state, CloseTarget::OtherTabs(state.active_tab_id)),
+            AppAction::CommandPalette => PaletteManager::toggle(state),
+            AppAction::IncreaseFontSize => FontManager::zoom_pane(state, 1.0),
+            AppAction::DecreaseFontSize => FontManager::zoom_pane(state, -1.0),
+            AppAction::ResetFontSize => FontManager::reset_pane(state),
+            AppAction::IncreaseGlobalFontSize => FontManager::zoom_all(state, 1.0),
+            AppAction::DecreaseGlobalFontSize => FontManager::zoom_all(state, -1.0),
+            AppAction::ResetGlobalFontSize => FontManager::reset_all(state),
+            AppAction::SelectTheme => ThemeManager::open_picker(state),
+            AppAction::JoinTab(index) => {
+                if let (Some(&tab_id), Some(terminal_id)) = (state.tab_order.get(index), state.focused_terminal) {
+                    SplitManager::join_tab(state, tab_id, terminal_id);
+                }
+            }
+            AppAction::SwitchToTab(index) => TabManager::switch_to_tab_by_index(state, index),
+        }
+    }
+    
+    /// Close a pane or tab, or ask first when a command other than the shell
+    /// is running in it
+    pub fn request_close(state: &mut AppState, target: CloseTarget) {
+        let terminal_ids = match target {
+            CloseTarget::Pane(terminal_id) => vec![terminal_id],
+            CloseTarget::Tab(tab_id) => state.tab_layouts.get(&tab_id)
+                .map(TabManager::collect_terminal_ids)
+                .unwrap_or_default(),
+            CloseTarget::OtherTabs(tab_id) => state.tab_layouts.iter()
+                .filter(|(other, _)| **other != tab_id)
+                .flat_map(|(_, layout)| TabManager::collect_terminal_ids(layout))
+                .collect(),
+        };
+        let running = terminal_ids.iter()
+            .filter_map(|terminal_id| state.terminals.get(terminal_id))
+            .find_map(|terminal| terminal.foreground_process());
+        
+        match running {
+            Some(command) => state.pending_close = Some(PendingClose { target, command }),
+            None => Self::close(state, target),
+        }
+    }
+    
+    /// Answer the pending close confirmation
+    pub fn confirm_close(state: &mut AppState, confirmed: bool) {
+        if let Some(pending) = state.pending_close.take() {
+            if confirmed {
+                Self::close(state, pending.target);
+            }
+        }
+    }
+    
+    fn close(state: &mut AppState, target: CloseTarget) {
+        match target {
+            CloseTarget::Pane(terminal_id) => SplitManager::close_pane(state, terminal_id),
+            CloseTarget::Tab(tab_id) => TabManager::close_tab(state, tab_id),
+            CloseTarget::OtherTabs(tab_id) => {
+                for other in state.tab_order.clone() {
+                    if other != tab_id {
+                        TabManager::close_tab(state, other);
+                    }
+                }
+                TabManager::switch_to_tab(state, tab_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_from_name() {
+        for (name, action) in NAMED_ACTIONS {
+            assert_eq!(AppAction::from_name(name), Some(*action));
+        }
+        assert_eq!(AppAction::from_name("switch_to_tab_3"), Some(AppAction::SwitchToTab(2)));
+        assert_eq!(AppAction::from_name("switch_to_tab_0"), None);
+        assert_eq!(AppAction::from_name("switch_to_tab_10"), None);
+        assert_eq!(AppAction::from_name("join_tab_1"), Some(AppAction::JoinTab(0)));
+        assert_eq!(AppAction::from_name("fly"), None);
+    }
+}
@@ -1,8 +1,12 @@
-use crate::types::{AppState, ViewMode};
+use crate::types::{AppState, PanelContent, ViewMode};
 use crate::tab_manager::TabManager;
 use crate::split_manager::SplitManager;
 use crate::input_handler::InputHandler;
 use crate::ui_renderer::UiRenderer;
+use crate::config_manager::ConfigManager;
+use crate::session_manager::SessionManager;
+use crate::action_manager::ActionManager;
+use crate::font_manager::FontManager;
 use egui_term::PtyEvent;
 
 pub struct App {
@@ -12,42 +16,33 @@ pub struct App {
 impl App {
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         // Load and configure Korean fonts
-        Self::configure_korean_fonts(&cc.egui_ctx);
+        cc.egui_ctx.set_fonts(FontManager::font_definitions(None));
+        
+        // Ctrl+Plus/Minus/0 zoom the terminal font instead of the whole UI
+        cc.egui_ctx.options_mut(|options| options.zoom_with_keyboard = false);
         
         let mut state = AppState::new(cc);
         
-        // Create the first tab
-        TabManager::create_new_tab(&mut state);
+        // Rebuild the previous session, or start with a single tab
+        if !SessionManager::restore(&mut state, cc.storage) {
+            TabManager::create_new_tab(&mut state);
+        }
         
         Self { state }
     }
-    
-    fn configure_korean_fonts(ctx: &egui::Context) {
-        let mut fonts = egui::FontDefinitions::default();
-        
-        // Load D2Coding font data
-        let d2coding_font_data = include_bytes!("../fonts/D2Coding.ttf");
-        
-        // Insert D2Coding font
-        fonts.font_data.insert(
-            "D2Coding".to_owned(),
-            egui::FontData::from_static(d2coding_font_data).into(),
-        );
-        
-        // Insert D2Coding at the front of monospace fonts
-        fonts.families.get_mut(&egui::FontFamily::Monospace).unwrap()
-            .insert(0, "D2Coding".to_owned());
-        
-        // Also add to proportional for UI text that might contain Korean
-        fonts.families.get_mut(&egui::FontFamily::Proportional).unwrap()
-            .insert(0, "D2Coding".to_owned());
-        
-        // Apply font configuration
-        ctx.set_fonts(fonts);
-    }
 }
 
 impl eframe::App for App {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        SessionManager::save(&self.state, storage);
+    }
+    
+    /// Save periodically as well as on exit, so a crash or logout keeps a
+    /// recent session
+    fn auto_save_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(30)
+    }
+    
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Consume Tab key events before egui can process them for UI focus
         // This ensures Tab keys go to terminals, not UI navigation
@@ -60,12 +55,27 @@ impl eframe::App for App {
             });
         }
         
+        // Pick up edits to the config file
+        ConfigManager::reload_if_changed(&mut self.state);
+        
         // Handle PTY events
         while let Ok((terminal_id, event)) = self.state.pty_proxy_receiver.try_recv() {
             match event {
                 PtyEvent::Exit => {
                     SplitManager::handle_terminal_exit(&mut self.state, terminal_id, ctx);
                 }
+                PtyEvent::Title(title) => {
+                    self.state.terminal_titles.insert(terminal_id, title);
+                }
+                PtyEvent::ResetTitle => {
+                    self.state.terminal_titles.remove(&terminal_id);
+                }
+                PtyEvent::ColorRequest(index, format) => {
+                    // OSC 4/10/11/12 query: answer with the pane's current color
+                    if let Some(terminal) = self.state.terminals.get(&terminal_id) {
+                        terminal.answer_color_request(&self.state.terminal_theme, index, format.as_ref());
+                    }
+                }
                 _ => {}
             }
         }
@@ -82,17 +92,26 @@ impl eframe::App for App {
         
         // Bottom panel for status
         egui::TopBottomPanel::bottom("status_panel").show(ctx, |ui| {
-            UiRenderer::render_status_bar(&self.state, ui);
+            UiRenderer::render_status_bar(&mut self.state, ui);
         });
 
+        // Remember focus order for directional navigation
+        SplitManager::record_focus(&mut self.state);
+        TabManager::record_active_tab(&mut self.state);
+        
         // Main terminal area
+        self.state.pane_rects.clear();
         egui::CentralPanel::default().show(ctx, |ui| {
             let available_rect = ui.available_rect_before_wrap();
             
             match self.state.view_mode {
                 ViewMode::Single => {
                     let active_tab_id = self.state.active_tab_id;
-                    if let Some(layout) = self.state.tab_layouts.get(&active_tab_id).cloned() {
+                    if let Some(terminal_id) = SplitManager::zoomed_terminal(&self.state, active_tab_id) {
+                        // Zoomed pane fills the tab, the stored layout stays untouched
+                        let mut zoomed = PanelContent::Terminal(terminal_id);
+                        UiRenderer::render_panel_content(&mut self.state, ui, &mut zoomed, available_rect);
+                    } else if let Some(layout) = self.state.tab_layouts.get(&active_tab_id).cloned() {
                         let mut layout_copy = layout;
                         UiRenderer::render_panel_content(&mut self.state, ui, &mut layout_copy, available_rect);
                         // Update the layout if it was modified (for resize)
@@ -104,5 +123,18 @@ impl eframe::App for App {
                 }
             }
         });
+        
+        UiRenderer::render_pane_drag(&mut self.state, ctx);
+        
+        // Menu picks are applied once the layouts rendered this frame are stored
+        for action in std::mem::take(&mut self.state.pending_actions) {
+            ActionManager::dispatch(&mut self.state, action);
+        }
+        
+        UiRenderer::render_close_confirmation(&mut self.state, ctx);
+        UiRenderer::render_export_dialog(&mut self.state, ctx);
+        UiRenderer::render_tab_switcher(&self.state, ctx);
+        UiRenderer::render_command_palette(&mut self.state, ctx);
+        UiRenderer::render_theme_picker(&mut self.state, ctx);
     }
 }
@@ -1,5 +1,6 @@
 use crate::types::AppState;
 use egui_term::BackendCommand;
+use std::collections::HashSet;
 
 pub struct BroadcastManager;
 
@@ -25,6 +26,17 @@ impl BroadcastManager {
         }
     }
     
+    /// Add or remove one pane from the broadcast; outside broadcast mode this
+    /// starts broadcasting to just that pane
+    pub fn toggle_pane_broadcast(state: &mut AppState, terminal_id: u64) {
+        if state.broadcast_mode {
+            Self::toggle_terminal_selection(state, terminal_id);
+        } else {
+            state.broadcast_mode = true;
+            state.selected_terminals = HashSet::from([terminal_id]);
+        }
+    }
+    
     /// Toggle selection of all terminals
     pub fn toggle_all_terminals_selection(state: &mut AppState) {
         if state.broadcast_mode {
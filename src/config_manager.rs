@@ -0,0 +1,536 @@
+use crate::types::AppState;
+use crate::keybindings::KeyBindings;
+use crate::theme_manager::ThemeManager;
+use crate::font_manager::FontManager;
+use egui_term::{BackendSettings, ColorPalette, FontSettings, ScrollbackLimit, TerminalFont, TerminalTheme};
+use serde::{Deserialize, Deserializer};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, SystemTime};
+
+/// How often the config file is checked for modifications
+const CONFIG_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Typed contents of `~/.config/tterm/config.toml`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub shell: ShellConfig,
+    pub font: FontConfig,
+    pub theme: ThemeConfig,
+    pub scrollback: ScrollbackConfig,
+    pub session: SessionConfig,
+    pub tabs: TabsConfig,
+    pub mouse: MouseConfig,
+    pub leader: LeaderConfig,
+    /// Action name -> shortcut, e.g. `new_tab = "Ctrl+Shift+T"`; `""` unbinds
+    pub keybindings: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShellConfig {
+    /// Shell program, falls back to `$SHELL` when not set
+    pub program: Option<String>,
+    pub args: Vec<String>,
+    pub env: HashMap<String, String>,
+    pub working_directory: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FontConfig {
+    pub size: f32,
+    /// Optional `.ttf`/`.otf` file used in front of the bundled D2Coding font
+    pub path: Option<PathBuf>,
+}
+
+impl Default for FontConfig {
+    fn default() -> Self {
+        Self {
+            size: FontSettings::default().font_type.size,
+            path: None,
+        }
+    }
+}
+
+/// A bundled or imported color scheme plus palette overrides on top of it,
+/// e.g. `background = "#1e1e1e"`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ThemeConfig {
+    /// Bundled theme such as `"Nord"`
+    pub name: Option<String>,
+    /// alacritty TOML/YAML, base16 YAML or iTerm2 `.itermcolors` scheme to import
+    pub file: Option<PathBuf>,
+    #[serde(flatten)]
+    pub colors: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScrollbackConfig {
+    /// Lines kept per terminal: a count, 0 to disable scrollback, or `"unlimited"`
+    #[serde(deserialize_with = "deserialize_scrollback_lines")]
+    pub lines: ScrollbackLimit,
+}
+
+impl Default for ScrollbackConfig {
+    fn default() -> Self {
+        Self {
+            lines: BackendSettings::default().scrollback,
+        }
+    }
+}
+
+fn deserialize_scrollback_lines<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ScrollbackLimit, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Lines {
+        Count(usize),
+        Keyword(String),
+    }
+    
+    match Lines::deserialize(deserializer)? {
+        Lines::Count(count) => Ok(ScrollbackLimit::Lines(count)),
+        Lines::Keyword(keyword) if keyword == "unlimited" => Ok(ScrollbackLimit::Unlimited),
+        Lines::Keyword(keyword) => Err(serde::de::Error::custom(format!(
+            "scrollback.lines: expected a line count or \"unlimited\", found `{}`", keyword
+        ))),
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SessionConfig {
+    /// Rebuild the previous tabs and splits on launch
+    pub restore: bool,
+    /// Output lines replayed into each restored pane, 0 disables replay
+    pub scrollback_lines: usize,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            restore: true,
+            scrollback_lines: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TabsConfig {
+    /// next_tab/previous_tab open a most-recently-used switcher instead of
+    /// stepping through the tab bar order
+    pub mru_switcher: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MouseConfig {
+    /// Modifiers held while dragging to select a rectangle, `""` disables block selection
+    pub block_selection: String,
+}
+
+impl Default for MouseConfig {
+    fn default() -> Self {
+        Self {
+            block_selection: String::from("Alt"),
+        }
+    }
+}
+
+/// tmux-style prefix key: the leader arms a one-shot mode where the next key picks an action
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LeaderConfig {
+    /// Leader shortcut, e.g. `"Ctrl+A"`; leader mode is off when not set
+    pub key: Option<String>,
+    /// How long the leader stays armed
+    pub timeout_ms: u64,
+    /// Action name -> key pressed after the leader
+    pub keybindings: BTreeMap<String, String>,
+}
+
+impl Default for LeaderConfig {
+    fn default() -> Self {
+        Self {
+            key: None,
+            timeout_ms: 1500,
+            keybindings: BTreeMap::new(),
+        }
+    }
+}
+
+/// Apply the `[theme]` color overrides to a palette
+pub fn apply_theme_colors(palette: &mut ColorPalette, theme: &ThemeConfig) -> Result<(), String> {
+    for (name, value) in &theme.colors {
+        set_palette_color(palette, name, value)?;
+    }
+    Ok(())
+}
+
+/// Set a single palette entry by its config name
+pub fn set_palette_color(palette: &mut ColorPalette, name: &str, value: &str) -> Result<(), String> {
+    if !is_hex_color(value) {
+        return Err(format!("theme.{}: `{}` is not a #rrggbb color", name, value));
+    }
+    let value = value.to_string();
+    let slot = match name {
+        "foreground" => &mut palette.foreground,
+        "background" => &mut palette.background,
+        "black" => &mut palette.black,
+        "red" => &mut palette.red,
+        "green" => &mut palette.green,
+        "yellow" => &mut palette.yellow,
+        "blue" => &mut palette.blue,
+        "magenta" => &mut palette.magenta,
+        "cyan" => &mut palette.cyan,
+        "white" => &mut palette.white,
+        "bright_black" => &mut palette.bright_black,
+        "bright_red" => &mut palette.bright_red,
+        "bright_green" => &mut palette.bright_green,
+        "bright_yellow" => &mut palette.bright_yellow,
+        "bright_blue" => &mut palette.bright_blue,
+        "bright_magenta" => &mut palette.bright_magenta,
+        "bright_cyan" => &mut palette.bright_cyan,
+        "bright_white" => &mut palette.bright_white,
+        "bright_foreground" => {
+            palette.bright_foreground = Some(value);
+            return Ok(());
+        }
+        "dim_foreground" => &mut palette.dim_foreground,
+        "dim_black" => &mut palette.dim_black,
+        "dim_red" => &mut palette.dim_red,
+        "dim_green" => &mut palette.dim_green,
+        "dim_yellow" => &mut palette.dim_yellow,
+        "dim_blue" => &mut palette.dim_blue,
+        "dim_magenta" => &mut palette.dim_magenta,
+        "dim_cyan" => &mut palette.dim_cyan,
+        "dim_white" => &mut palette.dim_white,
+        _ => return Err(format!("theme: unknown color `{}`", name)),
+    };
+    *slot = value;
+    Ok(())
+}
+
+/// `TerminalTheme` panics on malformed colors, so validate before building one
+fn is_hex_color(value: &str) -> bool {
+    value.len() == 7 && value.starts_with('#') && value[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Expand a leading `~` to the home directory
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), std::env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Show a path under the home directory as `~/...`
+pub fn collapse_home(path: &Path) -> String {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    match home.as_deref().and_then(|home| path.strip_prefix(home).ok()) {
+        Some(relative) => Path::new("~").join(relative).display().to_string(),
+        None => path.display().to_string(),
+    }
+}
+
+/// Everything derived from a successfully parsed config
+struct ResolvedConfig {
+    config: Config,
+    font: TerminalFont,
+    theme: TerminalTheme,
+    theme_name: String,
+    key_bindings: KeyBindings,
+}
+
+pub struct ConfigManager;
+
+impl ConfigManager {
+    /// Location of the config file
+    pub fn config_path() -> Option<PathBuf> {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .map(|dir| dir.join("tterm").join("config.toml"))
+    }
+
+    /// Load the config file at startup; a missing file means defaults
+    pub fn load(state: &mut AppState) {
+        Self::reload(state);
+        // No terminal is running yet to miss the shell settings
+        state.config_notice = None;
+    }
+
+    /// Poll the config file's modification time on a background thread;
+    /// the receiver gets a message and the UI a repaint on every change
+    pub fn watch(ctx: egui::Context) -> Receiver<()> {
+        let (sender, receiver) = mpsc::channel();
+        let mut modified = Self::config_modified_time();
+        let spawned = std::thread::Builder::new()
+            .name("config_watcher".to_string())
+            .spawn(move || loop {
+                std::thread::sleep(CONFIG_CHECK_INTERVAL);
+                let current = Self::config_modified_time();
+                if current != modified {
+                    modified = current;
+                    if sender.send(()).is_err() {
+                        break;
+                    }
+                    ctx.request_repaint();
+                }
+            });
+        if let Err(e) = spawned {
+            log::warn!("Failed to start the config watcher: {}", e);
+        }
+        receiver
+    }
+
+    /// Re-read the config file if the watcher saw it change
+    pub fn reload_if_changed(state: &mut AppState) {
+        if state.config_changes.try_iter().count() > 0 {
+            Self::reload(state);
+        }
+    }
+
+    /// Build backend settings for a new terminal from the shell config
+    pub fn backend_settings(config: &Config) -> BackendSettings {
+        let mut settings = Self::default_backend_settings(config);
+        if let Some(program) = &config.shell.program {
+            settings.shell = program.clone();
+        }
+        settings.args = config.shell.args.clone();
+        settings.env.extend(config.shell.env.clone());
+        settings.working_directory = config.shell.working_directory.as_deref().map(expand_home);
+        settings
+    }
+
+    /// Backend settings with `$SHELL` and no working directory, for when the
+    /// configured shell fails to start
+    pub fn default_backend_settings(config: &Config) -> BackendSettings {
+        let mut settings = BackendSettings::default();
+        if let Ok(shell) = std::env::var("SHELL") {
+            settings.shell = shell;
+        }
+        settings.scrollback = config.scrollback.lines;
+        settings
+    }
+
+    fn config_modified_time() -> Option<SystemTime> {
+        Self::config_path()
+            .and_then(|path| std::fs::metadata(path).ok())
+            .and_then(|metadata| metadata.modified().ok())
+    }
+
+    /// Parse the config file and apply it, keeping the previous config on errors
+    fn reload(state: &mut AppState) {
+        let contents = match Self::config_path().map(std::fs::read_to_string) {
+            Some(Ok(contents)) => contents,
+            Some(Err(e)) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Some(Err(e)) => {
+                state.config_error = Some(format!("config.toml: {}", e));
+                return;
+            }
+            None => String::new(),
+        };
+
+        match Self::resolve(&contents) {
+            Ok(resolved) => {
+                Self::apply(state, resolved);
+                state.config_error = None;
+            }
+            Err(e) => {
+                log::warn!("Failed to load config: {}", e);
+                state.config_error = Some(format!("config.toml: {}", e));
+            }
+        }
+    }
+
+    fn resolve(contents: &str) -> Result<ResolvedConfig, String> {
+        let config: Config = toml::from_str(contents).map_err(|e| e.message().to_string())?;
+
+        let (theme_name, mut palette) = ThemeManager::configured_palette(&config.theme)?;
+        apply_theme_colors(&mut palette, &config.theme)?;
+
+        if !(config.font.size.is_finite() && config.font.size > 0.0) {
+            return Err(format!("font.size: {} is not a valid size", config.font.size));
+        }
+        let font = TerminalFont::new(FontSettings {
+            font_type: egui::FontId::monospace(config.font.size),
+        });
+
+        if let Some(dir) = &config.shell.working_directory {
+            if !expand_home(dir).is_dir() {
+                return Err(format!("shell.working_directory: {} is not a directory", dir.display()));
+            }
+        }
+
+        let key_bindings = KeyBindings::from_config(&config)?;
+
+        Ok(ResolvedConfig {
+            config,
+            font,
+            theme: TerminalTheme::new(Box::new(palette)),
+            theme_name,
+            key_bindings,
+        })
+    }
+
+    /// Apply a parsed config; running terminals pick up font and theme on
+    /// their next frame and the scrollback limit right away, shell settings
+    /// are used for terminals created later
+    fn apply(state: &mut AppState, resolved: ResolvedConfig) {
+        if resolved.config.font.path != state.config.font.path {
+            Self::load_font_file(state, resolved.config.font.path.as_deref().map(expand_home).as_deref());
+        }
+
+        if resolved.config.scrollback.lines != state.config.scrollback.lines {
+            for terminal in state.terminals.values_mut() {
+                terminal.set_scrollback(resolved.config.scrollback.lines);
+            }
+        }
+
+        state.config_notice = if Self::shell_changed(&state.config.shell, &resolved.config.shell) {
+            Some("Shell settings apply to new panes".to_string())
+        } else {
+            None
+        };
+
+        state.config = resolved.config;
+        state.terminal_font = resolved.font;
+        state.terminal_theme = resolved.theme;
+        state.theme_name = resolved.theme_name;
+        state.key_bindings = resolved.key_bindings;
+    }
+
+    fn shell_changed(old: &ShellConfig, new: &ShellConfig) -> bool {
+        old.program != new.program
+            || old.args != new.args
+            || old.env != new.env
+            || old.working_directory != new.working_directory
+    }
+
+    /// Put the font at `path` in front of the bundled fonts, or go back to
+    /// the bundled fonts alone when there is no path
+    fn load_font_file(state: &mut AppState, path: Option<&Path>) {
+        let font = path.and_then(|path| match std::fs::read(path) {
+            Ok(data) => Some((format!("config-font:{}", path.display()), data)),
+            Err(e) => {
+                log::warn!("Failed to read font {}: {}", path.display(), e);
+                None
+            }
+        });
+        state.egui_ctx.set_fonts(FontManager::font_definitions(font));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action_manager::AppAction;
+    use crate::keybindings::KeyInput;
+    use egui::{Key, Modifiers};
+    use egui_term::TerminalMode;
+
+    #[test]
+    fn test_empty_config_uses_defaults() {
+        let resolved = ConfigManager::resolve("").unwrap();
+        assert_eq!(resolved.config.font.size, 11.0);
+        assert_eq!(resolved.config.scrollback.lines, ScrollbackLimit::Lines(10_000));
+        assert!(resolved.config.session.restore);
+        assert_eq!(resolved.config.session.scrollback_lines, 0);
+        assert_eq!(resolved.config.keybindings.len(), 0);
+    }
+
+    #[test]
+    fn test_full_config() {
+        let resolved = ConfigManager::resolve(r##"
+            [shell]
+            program = "/bin/zsh"
+            args = ["-l"]
+            working_directory = "/tmp"
+            env = { TERM_PROGRAM = "tterm" }
+
+            [font]
+            size = 14.0
+
+            [theme]
+            background = "#1e1e1e"
+
+            [scrollback]
+            lines = 5000
+
+            [session]
+            scrollback_lines = 200
+
+            [keybindings]
+            new_tab = "Ctrl+Shift+T"
+
+            [leader]
+            key = "Ctrl+A"
+            timeout_ms = 1000
+            keybindings = { close_pane = "Q" }
+        "##).unwrap();
+
+        let settings = ConfigManager::backend_settings(&resolved.config);
+        assert_eq!(settings.shell, "/bin/zsh");
+        assert_eq!(settings.args, vec!["-l".to_string()]);
+        assert_eq!(settings.working_directory, Some(PathBuf::from("/tmp")));
+        assert_eq!(settings.env.get("TERM_PROGRAM").map(String::as_str), Some("tterm"));
+        assert_eq!(settings.scrollback, ScrollbackLimit::Lines(5000));
+        assert_eq!(resolved.config.session.scrollback_lines, 200);
+        assert_eq!(resolved.font.font_type().size, 14.0);
+        assert_eq!(
+            resolved.key_bindings.resolve(Key::T, Modifiers::CTRL | Modifiers::SHIFT, TerminalMode::empty()),
+            Some(KeyInput::App(AppAction::NewTab))
+        );
+        assert!(resolved.key_bindings.is_leader(Key::A, Modifiers::CTRL));
+        assert_eq!(resolved.key_bindings.leader_timeout, Duration::from_millis(1000));
+        assert_eq!(resolved.key_bindings.resolve_leader(Key::Q, Modifiers::NONE), Some(AppAction::ClosePane));
+    }
+
+    #[test]
+    fn test_scrollback_lines() {
+        let lines = |toml: &str| ConfigManager::resolve(toml).map(|resolved| resolved.config.scrollback.lines);
+        assert_eq!(lines("[scrollback]\nlines = 0").unwrap(), ScrollbackLimit::Lines(0));
+        assert_eq!(lines("[scrollback]\nlines = \"unlimited\"").unwrap(), ScrollbackLimit::Unlimited);
+        assert!(lines("[scrollback]\nlines = \"lots\"").is_err());
+        assert!(lines("[scrollback]\nlines = -1").is_err());
+    }
+
+    #[test]
+    fn test_theme_name_with_overrides() {
+        let resolved = ConfigManager::resolve("[theme]\nname = \"Dracula\"\nred = \"#ff0000\"").unwrap();
+        assert_eq!(resolved.theme_name, "Dracula");
+        let (_, mut palette) = ThemeManager::configured_palette(&resolved.config.theme).unwrap();
+        apply_theme_colors(&mut palette, &resolved.config.theme).unwrap();
+        assert_eq!(palette.background, "#282a36");
+        assert_eq!(palette.red, "#ff0000");
+
+        assert!(ConfigManager::resolve("[theme]\nname = \"Mauve\"").is_err());
+        assert!(ConfigManager::resolve("[theme]\nfile = \"/nonexistent/scheme.toml\"").is_err());
+    }
+
+    #[test]
+    fn test_invalid_config_is_an_error() {
+        assert!(ConfigManager::resolve("[font]\nsize = \"big\"").is_err());
+        assert!(ConfigManager::resolve("[unknown]").is_err());
+        assert!(ConfigManager::resolve("[theme]\nbackground = \"red\"").is_err());
+        assert!(ConfigManager::resolve("[theme]\nmauve = \"#ffffff\"").is_err());
+        assert!(ConfigManager::resolve("[keybindings]\nnew_tab = \"Ctrl+Nope\"").is_err());
+        assert!(ConfigManager::resolve("[keybindings]\nfly = \"Ctrl+F\"").is_err());
+        assert!(ConfigManager::resolve("[leader]\nkey = \"Ctrl+Nope\"").is_err());
+        assert!(ConfigManager::resolve("[mouse]\nblock_selection = \"Hyper\"").is_err());
+    }
+
+    #[test]
+    fn test_missing_working_directory_is_an_error() {
+        let error = ConfigManager::resolve("[shell]\nworking_directory = \"/nonexistent/tterm\"").err();
+        assert_eq!(
+            error.as_deref(),
+            Some("shell.working_directory: /nonexistent/tterm is not a directory")
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use crate::types::AppState;
+use crate::search_manager::SearchManager;
+use egui::{Key, Modifiers};
+use egui_term::{SelectionType, TerminalBackend, ViMotion};
+
+/// What a key does in copy mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CopyCommand {
+    Motion(ViMotion),
+    Select(SelectionType),
+    Yank,
+    Search,
+    SearchStep(bool), // forward
+    Exit,
+}
+
+pub struct CopyModeManager;
+
+impl CopyModeManager {
+    /// Enter or leave copy mode on the focused pane
+    pub fn toggle(state: &mut AppState) {
+        if let Some(terminal) = state.focused_terminal.and_then(|id| state.terminals.get_mut(&id)) {
+            terminal.toggle_vi_mode();
+        }
+    }
+    
+    /// True while the pane is in copy mode
+    pub fn is_active(state: &AppState, terminal_id: u64) -> bool {
+        state.terminals.get(&terminal_id).is_some_and(|terminal| terminal.is_vi_mode())
+    }
+    
+    /// True while the focused pane is in copy mode and takes the keyboard
+    pub fn has_keyboard(state: &AppState) -> bool {
+        state.focused_terminal.is_some_and(|terminal_id| Self::is_active(state, terminal_id))
+    }
+    
+    /// Typed characters: hjkl/w/b/e motions, v/V selection, y, / and n/N
+    pub fn handle_text(state: &mut AppState, ctx: &egui::Context, text: &str) {
+        for c in text.chars() {
+            if let Some(command) = Self::text_command(c) {
+                Self::run(state, ctx, command);
+            }
+        }
+    }
+    
+    /// Keys without text: Escape, arrows and Ctrl+V or Ctrl+Q for block selection
+    pub fn handle_key(state: &mut AppState, ctx: &egui::Context, key: Key, modifiers: Modifiers) {
+        if let Some(command) = Self::key_command(key, modifiers) {
+            Self::run(state, ctx, command);
+        }
+    }
+    
+    fn text_command(c: char) -> Option<CopyCommand> {
+        let command = match c {
+            'h' => CopyCommand::Motion(ViMotion::Left),
+            'j' => CopyCommand::Motion(ViMotion::Down),
+            'k' => CopyCommand::Motion(ViMotion::Up),
+            'l' => CopyCommand::Motion(ViMotion::Right),
+            'w' => CopyCommand::Motion(ViMotion::SemanticRight),
+            'b' => CopyCommand::Motion(ViMotion::SemanticLeft),
+            'e' => CopyCommand::Motion(ViMotion::SemanticRightEnd),
+            'W' => CopyCommand::Motion(ViMotion::WordRight),
+            'B' => CopyCommand::Motion(ViMotion::WordLeft),
+            'E' => CopyCommand::Motion(ViMotion::WordRightEnd),
+            '0' => CopyCommand::Motion(ViMotion::First),
+            '^' => CopyCommand::Motion(ViMotion::FirstOccupied),
+            '$' => CopyCommand::Motion(ViMotion::Last),
+            'H' => CopyCommand::Motion(ViMotion::High),
+            'M' => CopyCommand::Motion(ViMotion::Middle),
+            'L' => CopyCommand::Motion(ViMotion::Low),
+            '%' => CopyCommand::Motion(ViMotion::Bracket),
+            'v' => CopyCommand::Select(SelectionType::Simple),
+            'V' => CopyCommand::Select(SelectionType::Lines),
+            'y' => CopyCommand::Yank,
+            '/' => CopyCommand::Search,
+            'n' => CopyCommand::SearchStep(false),
+            'N' => CopyCommand::SearchStep(true),
+            _ => return None,
+        };
+        Some(command)
+    }
+    
+    fn key_command(key: Key, modifiers: Modifiers) -> Option<CopyCommand> {
+        let command = match key {
+            Key::Escape => CopyCommand::Exit,
+            // Outside macOS, Ctrl+V only arrives as a paste of a non-empty clipboard
+            Key::V | Key::Q if modifiers.ctrl && !modifiers.shift => CopyCommand::Select(SelectionType::Block),
+            Key::ArrowLeft => CopyCommand::Motion(ViMotion::Left),
+            Key::ArrowDown => CopyCommand::Motion(ViMotion::Down),
+            Key::ArrowUp => CopyCommand::Motion(ViMotion::Up),
+            Key::ArrowRight => CopyCommand::Motion(ViMotion::Right),
+            Key::Home => CopyCommand::Motion(ViMotion::First),
+            Key::End => CopyCommand::Motion(ViMotion::Last),
+            _ => return None,
+        };
+        Some(command)
+    }
+    
+    fn run(state: &mut AppState, ctx: &egui::Context, command: CopyCommand) {
+        let Some(terminal_id) = state.focused_terminal else {
+            return;
+        };
+        
+        match command {
+            CopyCommand::Search => SearchManager::open_find_bar(state),
+            CopyCommand::SearchStep(forward) => SearchManager::step(state, terminal_id, forward),
+            command => {
+                if let Some(terminal) = state.terminals.get_mut(&terminal_id) {
+                    Self::apply(terminal, ctx, command);
+                }
+            }
+        }
+    }
+    
+    fn apply(terminal: &mut TerminalBackend, ctx: &egui::Context, command: CopyCommand) {
+        match command {
+            CopyCommand::Motion(motion) => terminal.vi_motion(motion),
+            CopyCommand::Select(selection_type) => terminal.vi_select(selection_type),
+            CopyCommand::Yank => {
+                let text = terminal.selectable_content();
+                if !text.is_empty() {
+                    ctx.copy_text(text);
+                    terminal.clear_selection();
+                }
+            }
+            CopyCommand::Exit => terminal.toggle_vi_mode(),
+            CopyCommand::Search | CopyCommand::SearchStep(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_mode_commands() {
+        assert_eq!(CopyModeManager::text_command('j'), Some(CopyCommand::Motion(ViMotion::Down)));
+        assert_eq!(CopyModeManager::text_command('V'), Some(CopyCommand::Select(SelectionType::Lines)));
+        assert_eq!(CopyModeManager::text_command('x'), None);
+        assert_eq!(
+            CopyModeManager::key_command(Key::V, Modifiers::CTRL | Modifiers::COMMAND),
+            Some(CopyCommand::Select(SelectionType::Block))
+        );
+        assert_eq!(
+            CopyModeManager::key_command(Key::Q, Modifiers::CTRL),
+            Some(CopyCommand::Select(SelectionType::Block))
+        );
+        assert_eq!(CopyModeManager::key_command(Key::V, Modifiers::NONE), None);
+        assert_eq!(CopyModeManager::key_command(Key::Escape, Modifiers::NONE), Some(CopyCommand::Exit));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::types::AppState;
+use crate::config_manager::expand_home;
+use egui_term::ExportFormat;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+/// "Save Output As…" dialog of one pane
+#[derive(Debug)]
+pub struct ExportDialog {
+    pub terminal_id: u64,
+    pub path: String,
+    pub format: ExportFormat,
+    pub error: Option<String>, // Set when writing the file failed
+    pub replace: bool, // Set once the user was told the file exists; saving again replaces it
+    pub saving: Option<Receiver<Result<(), String>>>, // Outcome of the write running in the background
+}
+
+pub struct ExportManager;
+
+impl ExportManager {
+    /// Open the save dialog for a pane, suggesting a file in its working directory
+    pub fn open_dialog(state: &mut AppState, terminal_id: u64) {
+        let Some(terminal) = state.terminals.get(&terminal_id) else {
+            return;
+        };
+        let directory = terminal.working_directory()
+            .or_else(|| std::env::var_os("HOME").map(PathBuf::from))
+            .unwrap_or_default();
+        let format = ExportFormat::default();
+        let path = directory.join(format!("tterm-output-{}.{}", terminal_id, format.extension()));
+        
+        state.export_dialog = Some(ExportDialog {
+            terminal_id,
+            path: path.display().to_string(),
+            format,
+            error: None,
+            replace: false,
+            saving: None,
+        });
+    }
+    
+    /// Switch the format, keeping the path's extension in step
+    pub fn set_format(state: &mut AppState, format: ExportFormat) {
+        if let Some(dialog) = &mut state.export_dialog {
+            dialog.path = Self::with_extension(&dialog.path, format);
+            dialog.format = format;
+            dialog.replace = false;
+        }
+    }
+    
+    /// Forget an earlier "replace it?" answer once the path is edited
+    pub fn path_changed(state: &mut AppState) {
+        if let Some(dialog) = &mut state.export_dialog {
+            dialog.replace = false;
+        }
+    }
+    
+    /// Write the pane's history to the chosen file in the background. An existing file is only
+    /// replaced on the second save, after the dialog has said it exists
+    pub fn save(state: &mut AppState) {
+        let Some(dialog) = &mut state.export_dialog else {
+            return;
+        };
+        if dialog.saving.is_some() {
+            return;
+        }
+        let Some(terminal) = state.terminals.get(&dialog.terminal_id) else {
+            state.export_dialog = None;
+            return;
+        };
+        
+        let path = expand_home(Path::new(dialog.path.trim()));
+        if path.exists() && !dialog.replace {
+            dialog.replace = true;
+            dialog.error = None;
+            return;
+        }
+        
+        // Serialize and write off the UI thread; only the grid copy happens here
+        let snapshot = terminal.history_snapshot();
+        let format = dialog.format;
+        let theme = state.terminal_theme.clone();
+        let ctx = state.egui_ctx.clone();
+        let (sender, receiver) = mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name("export_output".to_string())
+            .spawn(move || {
+                let output = snapshot.export(format, &theme);
+                let result = std::fs::write(&path, output).map_err(|error| error.to_string());
+                let _ = sender.send(result);
+                ctx.request_repaint();
+            });
+        match spawned {
+            Ok(_) => dialog.saving = Some(receiver),
+            Err(error) => dialog.error = Some(error.to_string()),
+        }
+    }
+    
+    /// Close the dialog once the background write succeeded; on failure it stays open with the error
+    pub fn poll_save(state: &mut AppState) {
+        let Some(dialog) = &mut state.export_dialog else {
+            return;
+        };
+        let result = match dialog.saving.as_ref().map(Receiver::try_recv) {
+            Some(Ok(result)) => result,
+            Some(Err(TryRecvError::Disconnected)) => Err("The export stopped unexpectedly".to_string()),
+            _ => return,
+        };
+        dialog.saving = None;
+        match result {
+            Ok(()) => state.export_dialog = None,
+            Err(error) => {
+                dialog.replace = false;
+                dialog.error = Some(error);
+            }
+        }
+    }
+    
+    /// Replace an export format's extension, leaving other file names alone
+    fn with_extension(path: &str, format: ExportFormat) -> String {
+        let current = Path::new(path);
+        let known = current.extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| ExportFormat::ALL.iter().any(|other| other.extension() == extension));
+        
+        if known {
+            current.with_extension(format.extension()).display().to_string()
+        } else {
+            path.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_changes_known_extensions() {
+        assert_eq!(ExportManager::with_extension("/tmp/out.txt", ExportFormat::Html), "/tmp/out.html");
+        assert_eq!(ExportManager::with_extension("/tmp/out.html", ExportFormat::Ansi), "/tmp/out.ans");
+        assert_eq!(ExportManager::with_extension("/tmp/out.log", ExportFormat::Html), "/tmp/out.log");
+        assert_eq!(ExportManager::with_extension("/tmp/out", ExportFormat::Html), "/tmp/out");
+    }
+}
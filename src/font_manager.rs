@@ -0,0 +1,123 @@
+use crate::types::AppState;
+use egui_term::{FontSettings, TerminalFont};
+use std::time::{Duration, Instant};
+
+/// Smallest and largest font size zooming can reach, in points
+const MIN_FONT_SIZE: f32 = 6.0;
+const MAX_FONT_SIZE: f32 = 72.0;
+
+/// Points added or removed by one zoom step
+const FONT_SIZE_STEP: f32 = 1.0;
+
+/// How long the size overlay stays up after a zoom
+const OVERLAY_DURATION: Duration = Duration::from_millis(1200);
+
+pub struct FontManager;
+
+impl FontManager {
+    /// Bundled fonts with D2Coding in front, and `extra` (name, font file
+    /// data) in front of those for the terminal's monospace family
+    pub fn font_definitions(extra: Option<(String, Vec<u8>)>) -> egui::FontDefinitions {
+        let mut fonts = egui::FontDefinitions::default();
+        
+        // Load D2Coding font data
+        let d2coding_font_data = include_bytes!("../fonts/D2Coding.ttf");
+        
+        // Insert D2Coding font
+        fonts.font_data.insert(
+            "D2Coding".to_owned(),
+            egui::FontData::from_static(d2coding_font_data).into(),
+        );
+        
+        // Insert D2Coding at the front of monospace fonts
+        fonts.families.get_mut(&egui::FontFamily::Monospace).unwrap()
+            .insert(0, "D2Coding".to_owned());
+        
+        // Also add to proportional for UI text that might contain Korean
+        fonts.families.get_mut(&egui::FontFamily::Proportional).unwrap()
+            .insert(0, "D2Coding".to_owned());
+        
+        // Configured font goes before D2Coding
+        if let Some((name, data)) = extra {
+            fonts.font_data.insert(name.clone(), egui::FontData::from_owned(data).into());
+            fonts.families.get_mut(&egui::FontFamily::Monospace).unwrap()
+                .insert(0, name);
+        }
+        
+        fonts
+    }
+    
+    /// Grow (positive `steps`) or shrink the focused pane's font
+    pub fn zoom_pane(state: &mut AppState, steps: f32) {
+        let Some(terminal_id) = state.focused_terminal else {
+            return;
+        };
+        let global = Self::global_size(state);
+        let size = Self::clamp(Self::font_size(state, terminal_id) + steps * FONT_SIZE_STEP);
+        state.pane_font_zoom.insert(terminal_id, size - global);
+        state.font_overlay = Some((Some(terminal_id), Instant::now()));
+    }
+    
+    /// Put the focused pane back on the global font size
+    pub fn reset_pane(state: &mut AppState) {
+        if let Some(terminal_id) = state.focused_terminal {
+            state.pane_font_zoom.remove(&terminal_id);
+            state.font_overlay = Some((Some(terminal_id), Instant::now()));
+        }
+    }
+    
+    /// Grow or shrink every pane's font; pane zooms stay relative to it
+    pub fn zoom_all(state: &mut AppState, steps: f32) {
+        let configured = state.terminal_font.font_type().size;
+        let size = Self::clamp(Self::global_size(state) + steps * FONT_SIZE_STEP);
+        state.font_zoom = size - configured;
+        state.font_overlay = Some((None, Instant::now()));
+    }
+    
+    /// Drop every zoom, back to the configured font size
+    pub fn reset_all(state: &mut AppState) {
+        state.font_zoom = 0.0;
+        state.pane_font_zoom.clear();
+        state.font_overlay = Some((None, Instant::now()));
+    }
+    
+    /// Size every pane uses unless it is zoomed on its own
+    pub fn global_size(state: &AppState) -> f32 {
+        Self::clamp(state.terminal_font.font_type().size + state.font_zoom)
+    }
+    
+    /// Font size of one pane
+    pub fn font_size(state: &AppState, terminal_id: u64) -> f32 {
+        let pane_zoom = state.pane_font_zoom.get(&terminal_id).copied().unwrap_or(0.0);
+        Self::clamp(Self::global_size(state) + pane_zoom)
+    }
+    
+    /// Configured font at the pane's zoomed size, for `TerminalView::set_font`
+    pub fn font(state: &AppState, terminal_id: u64) -> TerminalFont {
+        let mut font_type = state.terminal_font.font_type();
+        font_type.size = Self::font_size(state, terminal_id);
+        TerminalFont::new(FontSettings { font_type })
+    }
+    
+    /// True while the size overlay should be drawn on a pane; keeps
+    /// repainting until it goes away
+    pub fn overlay_visible(state: &AppState, ctx: &egui::Context, terminal_id: u64) -> bool {
+        let Some((target, zoomed_at)) = state.font_overlay else {
+            return false;
+        };
+        if target.is_some_and(|id| id != terminal_id) {
+            return false;
+        }
+        match OVERLAY_DURATION.checked_sub(zoomed_at.elapsed()) {
+            Some(remaining) if !remaining.is_zero() => {
+                ctx.request_repaint_after(remaining);
+                true
+            }
+            _ => false,
+        }
+    }
+    
+    fn clamp(size: f32) -> f32 {
+        size.clamp(MIN_FONT_SIZE, MAX_FONT_SIZE)
+    }
+}
@@ -174,13 +174,13 @@ impl GridManager {
             let new_right_total = 1.0 - left_ratio;
             
             // Scale left ratios
-            for i in 0..=col {
-                col_ratios[i] = col_ratios[i] * left_ratio / old_left_total;
+            for ratio in col_ratios.iter_mut().take(col + 1) {
+                *ratio = *ratio * left_ratio / old_left_total;
             }
             
             // Scale right ratios
-            for i in (col + 1)..cols {
-                col_ratios[i] = col_ratios[i] * new_right_total / old_right_total;
+            for ratio in col_ratios.iter_mut().take(cols).skip(col + 1) {
+                *ratio = *ratio * new_right_total / old_right_total;
             }
         }
     }
@@ -208,13 +208,13 @@ impl GridManager {
             let new_bottom_total = 1.0 - top_ratio;
             
             // Scale top ratios
-            for i in 0..=row {
-                row_ratios[i] = row_ratios[i] * top_ratio / old_top_total;
+            for ratio in row_ratios.iter_mut().take(row + 1) {
+                *ratio = *ratio * top_ratio / old_top_total;
             }
             
             // Scale bottom ratios
-            for i in (row + 1)..rows {
-                row_ratios[i] = row_ratios[i] * new_bottom_total / old_bottom_total;
+            for ratio in row_ratios.iter_mut().take(rows).skip(row + 1) {
+                *ratio = *ratio * new_bottom_total / old_bottom_total;
             }
         }
     }
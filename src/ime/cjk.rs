@@ -1,11 +1,14 @@
 use unicode_width::UnicodeWidthChar;
 
 /// Check if a character is a double-width character (CJK, emoji, etc.)
+/// Standalone Korean jamo are excluded: they only take a double-wide cell
+/// while being composed, which is handled by the IME overlay
 pub fn is_double_width_char(ch: char) -> bool {
-    ch.width().unwrap_or(1) == 2
+    !is_korean_jamo(ch) && ch.width().unwrap_or(1) == 2
 }
 
 /// Check if a string contains any double-width characters
+#[allow(dead_code)]
 pub fn contains_double_width_chars(text: &str) -> bool {
     text.chars().any(is_double_width_char)
 }
@@ -16,11 +19,13 @@ pub fn is_korean_jamo(ch: char) -> bool {
 }
 
 /// Check if a character is a completed CJK character (not in composition)
+#[allow(dead_code)]
 pub fn is_completed_cjk_char(ch: char) -> bool {
     is_double_width_char(ch) && !is_korean_jamo(ch)
 }
 
 /// Determine the appropriate cursor width for a character
+#[allow(dead_code)]
 pub fn get_cursor_width_for_char(ch: char) -> f32 {
     if is_double_width_char(ch) {
         2.0
@@ -173,10 +173,8 @@ impl KoreanInputState {
             let jung_idx = get_jungsung_index(jung)?;
             let jong_idx = self.jongsung.and_then(get_jongsung_index).unwrap_or(0);
             Some(compose_korean(cho_idx, jung_idx, jong_idx))
-        } else if let Some(cho) = self.chosung {
-            Some(cho)
         } else {
-            None
+            self.chosung
         }
     }
 
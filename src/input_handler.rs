@@ -1,110 +1,81 @@
-use crate::types::{AppState, SplitDirection};
-use crate::tab_manager::TabManager;
-use crate::split_manager::SplitManager;
-use crate::grid_manager::GridManager;
+use crate::types::AppState;
+use crate::action_manager::{ActionManager, AppAction};
 use crate::broadcast_manager::BroadcastManager;
+use crate::keybindings::KeyInput;
+use crate::tab_manager::TabManager;
+use crate::search_manager::SearchManager;
+use crate::copy_mode_manager::CopyModeManager;
 use crate::ime::korean::{KoreanInputState, is_consonant, is_vowel};
 use egui::{Key, Modifiers};
-use egui_term::BackendCommand;
+use egui_term::{BackendCommand, BindingAction, TerminalMode};
+use std::time::Instant;
 
 pub struct InputHandler;
 
 impl InputHandler {
-    /// Handle all keyboard shortcuts and input
-    pub fn handle_input(state: &mut AppState, ctx: &egui::Context) -> bool {
-        let mut handled_by_shortcuts = false;
+    /// Route this frame's keyboard input to app actions or the focused terminal
+    pub fn handle_input(state: &mut AppState, ctx: &egui::Context) {
+        Self::expire_leader(state, ctx);
         
-        // Handle Tab key specifically for terminal when focused
-        ctx.input(|i| {
-            if i.key_pressed(Key::Tab) && state.focused_terminal.is_some() {
-                // Send Tab directly to focused terminal, bypass UI focus system
-                Self::send_tab_to_focused_terminal(state);
-                handled_by_shortcuts = true;
-            }
-        });
+        if state.pending_close.is_some() {
+            Self::handle_close_confirmation(state, ctx);
+            return;
+        }
+        
+        // Text fields (tab title, find bar, dialogs) take the keyboard while focused
+        if state.renaming_tab.is_some()
+            || state.export_dialog.is_some()
+            || state.command_palette.is_some()
+            || state.theme_picker.is_some()
+            || SearchManager::has_keyboard(state, ctx)
+        {
+            return;
+        }
         
-        ctx.input(|i| {
-            if i.modifiers.command {
-                // Tab management shortcuts
-                if i.key_pressed(Key::T) {
-                    TabManager::create_new_tab(state);
-                    handled_by_shortcuts = true;
+        if Self::update_tab_switcher(state, ctx) {
+            return;
+        }
+        
+        let (events, current_modifiers) = ctx.input(|i| (i.events.clone(), i.modifiers));
+        
+        // A key consumed in leader mode must not also type its character
+        let mut swallow_next_text = false;
+        
+        for event in events {
+            let swallow_text = std::mem::take(&mut swallow_next_text);
+            match event {
+                egui::Event::Key { key, pressed: true, modifiers, .. } => {
+                    swallow_next_text = Self::handle_key(state, ctx, key, modifiers);
                 }
-                if i.key_pressed(Key::W) && state.tabs.len() > 1 {
-                    TabManager::close_tab(state, state.active_tab_id);
-                    handled_by_shortcuts = true;
+                // egui-winit turns the copy/cut/paste chords into these events
+                // without a key event, so map them back to their keys
+                egui::Event::Copy => {
+                    Self::handle_key(state, ctx, Key::C, current_modifiers);
                 }
-                
-                // Tab switching with Ctrl+1-9
-                for (idx, &key) in [Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8, Key::Num9].iter().enumerate() {
-                    if i.key_pressed(key) {
-                        TabManager::switch_to_tab_by_index(state, idx);
-                        handled_by_shortcuts = true;
-                    }
+                egui::Event::Cut => {
+                    Self::handle_key(state, ctx, Key::X, current_modifiers);
                 }
-                
-                // Split shortcuts
-                if i.modifiers.shift {
-                    if i.key_pressed(Key::D) {
-                        SplitManager::split_focused_panel(state, SplitDirection::Horizontal);
-                        handled_by_shortcuts = true;
-                    }
-                } else {
-                    if i.key_pressed(Key::D) {
-                        SplitManager::split_focused_panel(state, SplitDirection::Vertical);
-                        handled_by_shortcuts = true;
-                    }
+                egui::Event::Paste(_) if CopyModeManager::has_keyboard(state) => {
+                    CopyModeManager::handle_key(state, ctx, Key::V, current_modifiers);
                 }
-                
-                // Grid view toggle (Ctrl+S as per PRD)
-                if i.key_pressed(Key::S) {
-                    GridManager::toggle_grid_view(state);
-                    handled_by_shortcuts = true;
+                egui::Event::Paste(text) if state.paste_requested => {
+                    state.paste_requested = false;
+                    Self::write_text(state, &text);
                 }
-                
-                // Broadcast shortcuts
-                if i.modifiers.shift {
-                    if i.key_pressed(Key::B) {
-                        BroadcastManager::toggle_broadcast_mode(state);
-                        handled_by_shortcuts = true;
-                    }
-                    if i.key_pressed(Key::A) {
-                        BroadcastManager::toggle_all_terminals_selection(state);
-                        handled_by_shortcuts = true;
+                egui::Event::Paste(text) => {
+                    match Self::resolve_key(state, Key::V, current_modifiers) {
+                        Some(KeyInput::Terminal(BindingAction::Paste)) => Self::write_text(state, &text),
+                        Some(input) => Self::apply_key_input(state, ctx, input),
+                        None => {}
                     }
                 }
-            }
-            
-            // Panel navigation with Alt+Arrow keys
-            if i.modifiers.alt {
-                if i.key_pressed(Key::ArrowLeft) || i.key_pressed(Key::ArrowRight) ||
-                   i.key_pressed(Key::ArrowUp) || i.key_pressed(Key::ArrowDown) {
-                    SplitManager::navigate_focus_in_splits(state);
-                    handled_by_shortcuts = true;
+                egui::Event::Text(_) if swallow_text => {}
+                egui::Event::Text(text) if CopyModeManager::has_keyboard(state) => {
+                    CopyModeManager::handle_text(state, ctx, &text);
                 }
-            }
-        });
-        
-        // Handle direct keyboard input to focused terminal (if not handled by shortcuts)
-        if !handled_by_shortcuts {
-            Self::handle_direct_input_to_focused_terminal(state, ctx);
-        }
-        
-        handled_by_shortcuts
-    }
-    
-    /// Handle direct keyboard input to the focused terminal
-    fn handle_direct_input_to_focused_terminal(state: &mut AppState, ctx: &egui::Context) {
-        if let Some(focused_terminal_id) = state.focused_terminal {
-            let broadcast_mode = state.broadcast_mode;
-            
-            // First collect the events we need to process
-            let events = ctx.input(|i| i.events.clone());
-            
-            for event in events {
-                match event {
-                    egui::Event::Text(text) => {
-                        if broadcast_mode {
+                egui::Event::Text(text) => {
+                    if let Some(focused_terminal_id) = state.focused_terminal {
+                        if state.broadcast_mode {
                             BroadcastManager::broadcast_input(state, &text);
                         } else {
                             // Handle Korean input composition first
@@ -116,168 +87,172 @@ impl InputHandler {
                             }
                         }
                     }
-                    egui::Event::Key { key, pressed: true, modifiers, .. } => {
-                        // Handle keys that should finalize Korean composition
-                        match key {
-                            Key::Enter => {
-                                // Finalize any pending Korean composition before sending Enter
-                                Self::finalize_korean_composition(state, focused_terminal_id);
-                                if broadcast_mode {
-                                    BroadcastManager::broadcast_input(state, "\n");
-                                } else if let Some(terminal) = state.terminals.get_mut(&focused_terminal_id) {
-                                    terminal.process_command(BackendCommand::Write(b"\n".to_vec()));
-                                }
-                            }
-                            Key::Space => {
-                                // Space is handled by Text event, don't handle it here to avoid duplication
-                                // Just finalize composition if active
-                                Self::finalize_korean_composition(state, focused_terminal_id);
-                            }
-                            Key::Backspace => {
-                                // Handle backspace for Korean composition
-                                if let Some(korean_state) = state.korean_input_states.get_mut(&focused_terminal_id) {
-                                    if korean_state.is_composing {
-                                        korean_state.handle_backspace();
-                                        continue; // Don't send backspace to terminal, just update overlay
-                                    }
-                                }
-                                
-                                // If not handled by Korean IME, fall through to normal key processing
-                                if let Some(bytes) = Self::key_to_bytes(&key, &modifiers) {
-                                    if broadcast_mode {
-                                        BroadcastManager::broadcast_input(state, &String::from_utf8_lossy(&bytes));
-                                    } else if let Some(terminal) = state.terminals.get_mut(&focused_terminal_id) {
-                                        terminal.process_command(BackendCommand::Write(bytes));
-                                    }
-                                }
-                            }
-                            Key::ArrowUp | Key::ArrowDown | Key::ArrowLeft | Key::ArrowRight => {
-                                // Arrow keys should finalize Korean composition
-                                Self::finalize_korean_composition(state, focused_terminal_id);
-                                if let Some(bytes) = Self::key_to_bytes(&key, &modifiers) {
-                                    if broadcast_mode {
-                                        BroadcastManager::broadcast_input(state, &String::from_utf8_lossy(&bytes));
-                                    } else if let Some(terminal) = state.terminals.get_mut(&focused_terminal_id) {
-                                        terminal.process_command(BackendCommand::Write(bytes));
-                                    }
-                                }
-                            }
-                            Key::Escape => {
-                                // ESC should finalize Korean composition
-                                Self::finalize_korean_composition(state, focused_terminal_id);
-                                if let Some(bytes) = Self::key_to_bytes(&key, &modifiers) {
-                                    if broadcast_mode {
-                                        BroadcastManager::broadcast_input(state, &String::from_utf8_lossy(&bytes));
-                                    } else if let Some(terminal) = state.terminals.get_mut(&focused_terminal_id) {
-                                        terminal.process_command(BackendCommand::Write(bytes));
-                                    }
-                                }
-                            }
-                            _ => {
-                                // For other keys, handle normally
-                                if let Some(bytes) = Self::key_to_bytes(&key, &modifiers) {
-                                    if broadcast_mode {
-                                        BroadcastManager::broadcast_input(state, &String::from_utf8_lossy(&bytes));
-                                    } else if let Some(terminal) = state.terminals.get_mut(&focused_terminal_id) {
-                                        terminal.process_command(BackendCommand::Write(bytes));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    egui::Event::Paste(text) => {
-                        if broadcast_mode {
-                            BroadcastManager::broadcast_input(state, &text);
-                        } else if let Some(terminal) = state.terminals.get_mut(&focused_terminal_id) {
-                            terminal.process_command(BackendCommand::Write(text.as_bytes().to_vec()));
-                        }
-                    }
-                    _ => {}
                 }
+                _ => {}
             }
         }
     }
     
-    /// Convert key and modifiers to terminal bytes
-    fn key_to_bytes(key: &Key, modifiers: &Modifiers) -> Option<Vec<u8>> {
-        match key {
-            // Special keys
-            Key::Enter => Some(b"\r".to_vec()),
-            // Tab is handled separately to bypass UI focus system
-            Key::Tab => None,
-            Key::Backspace => Some(b"\x7f".to_vec()),
-            Key::Delete => Some(b"\x1b[3~".to_vec()),
-            Key::ArrowUp => Some(b"\x1b[A".to_vec()),
-            Key::ArrowDown => Some(b"\x1b[B".to_vec()),
-            Key::ArrowRight => Some(b"\x1b[C".to_vec()),
-            Key::ArrowLeft => Some(b"\x1b[D".to_vec()),
-            Key::Home => Some(b"\x1b[H".to_vec()),
-            Key::End => Some(b"\x1b[F".to_vec()),
-            Key::PageUp => Some(b"\x1b[5~".to_vec()),
-            Key::PageDown => Some(b"\x1b[6~".to_vec()),
-            Key::Escape => Some(b"\x1b".to_vec()),
-            _ => {
-                // Handle Ctrl combinations (but skip application shortcuts)
-                if modifiers.ctrl || modifiers.command {
-                    Self::ctrl_key_to_bytes(key, modifiers)
-                } else {
-                    None
+    /// While the close dialog is open keys only answer it: Enter closes, Escape cancels
+    fn handle_close_confirmation(state: &mut AppState, ctx: &egui::Context) {
+        let (confirm, cancel) = ctx.input(|i| (i.key_pressed(Key::Enter), i.key_pressed(Key::Escape)));
+        if confirm || cancel {
+            ActionManager::confirm_close(state, confirm);
+        }
+    }
+    
+    /// Pick the switcher's tab once its modifier is released; Escape cancels.
+    /// While it stays open only the next/previous tab keys get through.
+    /// Returns true when the frame's keys were consumed.
+    fn update_tab_switcher(state: &mut AppState, ctx: &egui::Context) -> bool {
+        let Some(held) = state.tab_switcher.as_ref().map(|switcher| switcher.held) else {
+            return false;
+        };
+        let (cancel, modifiers, events) = ctx.input(|i| (i.key_pressed(Key::Escape), i.modifiers, i.events.clone()));
+        if cancel {
+            TabManager::finish_switcher(state, false);
+            return true;
+        }
+        if !modifiers.contains(held) {
+            TabManager::finish_switcher(state, true);
+            return false;
+        }
+        
+        for event in events {
+            if let egui::Event::Key { key, pressed: true, modifiers, .. } = event {
+                if let Some(KeyInput::App(action @ (AppAction::NextTab | AppAction::PreviousTab))) =
+                    Self::resolve_key(state, key, modifiers)
+                {
+                    ActionManager::dispatch(state, action);
                 }
             }
         }
+        true
     }
     
-    /// Convert Ctrl key combinations to terminal bytes
-    fn ctrl_key_to_bytes(key: &Key, modifiers: &Modifiers) -> Option<Vec<u8>> {
-        // Skip shortcuts that are handled by application 
-        match key {
-            // Skip these as they are application shortcuts
-            Key::T | Key::W | Key::S 
-            | Key::Num1 | Key::Num2 | Key::Num3 
-            | Key::Num4 | Key::Num5 | Key::Num6 
-            | Key::Num7 | Key::Num8 | Key::Num9 => None,
-            Key::D if modifiers.shift => None, // Skip horizontal split
-            Key::D => None, // Skip vertical split
-            Key::B if modifiers.shift => None, // Skip broadcast toggle
-            Key::A if modifiers.shift => None, // Skip select all toggle
-            // Process regular Ctrl combinations for terminal
-            Key::A => Some(b"\x01".to_vec()),
-            Key::B => Some(b"\x02".to_vec()),
-            Key::C => Some(b"\x03".to_vec()),
-            Key::E => Some(b"\x05".to_vec()),
-            Key::F => Some(b"\x06".to_vec()),
-            Key::G => Some(b"\x07".to_vec()),
-            Key::H => Some(b"\x08".to_vec()),
-            Key::I => Some(b"\x09".to_vec()),
-            Key::J => Some(b"\x0a".to_vec()),
-            Key::K => Some(b"\x0b".to_vec()),
-            Key::L => Some(b"\x0c".to_vec()),
-            Key::M => Some(b"\x0d".to_vec()),
-            Key::N => Some(b"\x0e".to_vec()),
-            Key::O => Some(b"\x0f".to_vec()),
-            Key::P => Some(b"\x10".to_vec()),
-            Key::Q => Some(b"\x11".to_vec()),
-            Key::R => Some(b"\x12".to_vec()),
-            Key::U => Some(b"\x15".to_vec()),
-            Key::V => Some(b"\x16".to_vec()),
-            Key::X => Some(b"\x18".to_vec()),
-            Key::Y => Some(b"\x19".to_vec()),
-            Key::Z => Some(b"\x1a".to_vec()),
-            _ => None
+    /// Disarm the leader once its timeout has passed
+    fn expire_leader(state: &mut AppState, ctx: &egui::Context) {
+        if let Some(armed_at) = state.leader_armed_at {
+            match state.key_bindings.leader_timeout.checked_sub(armed_at.elapsed()) {
+                Some(remaining) if !remaining.is_zero() => ctx.request_repaint_after(remaining),
+                _ => state.leader_armed_at = None,
+            }
         }
     }
     
-    /// Send Tab key directly to focused terminal
-    fn send_tab_to_focused_terminal(state: &mut AppState) {
-        if let Some(focused_terminal_id) = state.focused_terminal {
-            let broadcast_mode = BroadcastManager::is_broadcast_mode_active(state);
-            
-            if broadcast_mode {
-                BroadcastManager::broadcast_input(state, "\t");
-            } else if let Some(terminal) = state.terminals.get_mut(&focused_terminal_id) {
-                let tab_bytes = b"\t".to_vec();
-                terminal.process_command(BackendCommand::Write(tab_bytes));
+    /// Arm the leader, or run the command key pressed while it is armed;
+    /// returns true when the key was consumed
+    fn handle_leader_key(state: &mut AppState, ctx: &egui::Context, key: Key, modifiers: Modifiers) -> bool {
+        if state.leader_armed_at.take().is_some() {
+            if state.key_bindings.is_leader(key, modifiers) {
+                // Pressing the leader twice sends it to the terminal, like tmux
+                let terminal_mode = Self::focused_terminal_mode(state);
+                if let Some(action) = state.key_bindings.resolve_terminal(key, modifiers, terminal_mode) {
+                    Self::apply_key_input(state, ctx, KeyInput::Terminal(action));
+                }
+            } else if let Some(action) = state.key_bindings.resolve_leader(key, modifiers) {
+                ActionManager::dispatch(state, action);
+            }
+            return true;
+        }
+        
+        if state.key_bindings.is_leader(key, modifiers) {
+            state.leader_armed_at = Some(Instant::now());
+            ctx.request_repaint_after(state.key_bindings.leader_timeout);
+            return true;
+        }
+        false
+    }
+    
+    fn focused_terminal_mode(state: &AppState) -> TerminalMode {
+        state.focused_terminal
+            .and_then(|terminal_id| state.terminals.get(&terminal_id))
+            .map(|terminal| terminal.last_content().terminal_mode)
+            .unwrap_or_default()
+    }
+    
+    /// Look up a key in the key bindings using the focused terminal's mode
+    fn resolve_key(state: &AppState, key: Key, modifiers: Modifiers) -> Option<KeyInput> {
+        state.key_bindings.resolve(key, modifiers, Self::focused_terminal_mode(state))
+    }
+    
+    /// Handle a key press; returns true when leader mode consumed it
+    fn handle_key(state: &mut AppState, ctx: &egui::Context, key: Key, modifiers: Modifiers) -> bool {
+        if Self::handle_leader_key(state, ctx, key, modifiers) {
+            return true;
+        }
+        
+        // App shortcuts such as new tab and split work without a focused pane
+        let Some(focused_terminal_id) = state.focused_terminal else {
+            if let Some(input @ KeyInput::App(_)) = Self::resolve_key(state, key, modifiers) {
+                Self::apply_key_input(state, ctx, input);
+            }
+            return false;
+        };
+        
+        // Copy mode keeps app shortcuts and copying; other keys drive its cursor
+        if CopyModeManager::has_keyboard(state) {
+            match Self::resolve_key(state, key, modifiers) {
+                Some(input @ (KeyInput::App(_) | KeyInput::Terminal(BindingAction::Copy))) => {
+                    Self::apply_key_input(state, ctx, input);
+                }
+                _ => CopyModeManager::handle_key(state, ctx, key, modifiers),
             }
+            return false;
+        }
+        
+        match Self::resolve_key(state, key, modifiers) {
+            Some(input @ KeyInput::Terminal(_)) if key == Key::Backspace => {
+                // Backspace edits a pending Korean composition instead of the terminal line
+                if let Some(korean_state) = state.korean_input_states.get_mut(&focused_terminal_id) {
+                    if korean_state.is_composing {
+                        korean_state.handle_backspace();
+                        return false;
+                    }
+                }
+                Self::apply_key_input(state, ctx, input);
+            }
+            Some(input) => Self::apply_key_input(state, ctx, input),
+            None => {
+                // Space is sent by the Text event; just finalize composition here
+                if key == Key::Space {
+                    Self::finalize_korean_composition(state, focused_terminal_id);
+                }
+            }
+        }
+        false
+    }
+    
+    fn apply_key_input(state: &mut AppState, ctx: &egui::Context, input: KeyInput) {
+        match input {
+            KeyInput::App(action) => ActionManager::dispatch(state, action),
+            KeyInput::Terminal(BindingAction::Char(c)) => {
+                Self::write_key_text(state, c.encode_utf8(&mut [0; 4]));
+            }
+            KeyInput::Terminal(BindingAction::Esc(sequence)) => Self::write_key_text(state, &sequence),
+            KeyInput::Terminal(BindingAction::Copy) => {
+                if let Some(terminal) = state.focused_terminal.and_then(|id| state.terminals.get(&id)) {
+                    ctx.copy_text(terminal.selectable_content());
+                }
+            }
+            KeyInput::Terminal(_) => {}
+        }
+    }
+    
+    /// Send key output to the terminal, finalizing any Korean composition first
+    fn write_key_text(state: &mut AppState, text: &str) {
+        if let Some(focused_terminal_id) = state.focused_terminal {
+            Self::finalize_korean_composition(state, focused_terminal_id);
+        }
+        Self::write_text(state, text);
+    }
+    
+    /// Send text to the focused terminal, or to all selected terminals when broadcasting
+    fn write_text(state: &mut AppState, text: &str) {
+        if state.broadcast_mode {
+            BroadcastManager::broadcast_input(state, text);
+        } else if let Some(terminal) = state.focused_terminal.and_then(|id| state.terminals.get_mut(&id)) {
+            terminal.process_command(BackendCommand::Write(text.as_bytes().to_vec()));
         }
     }
     
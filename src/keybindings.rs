@@ -0,0 +1,420 @@
+use crate::action_manager::AppAction;
+use crate::types::FocusDirection;
+use crate::config_manager::{Config, LeaderConfig};
+use egui::{Key, KeyboardShortcut, Modifiers};
+use egui_term::{generate_bindings, BindingAction, BindingsLayout, InputKind, KeyboardBinding, TerminalMode};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Where a key press should go
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyInput {
+    App(AppAction),
+    Terminal(BindingAction),
+}
+
+/// Single key table: application bindings are checked first, everything
+/// else is translated by egui_term's terminal bindings and sent to the PTY
+#[derive(Debug, Clone)]
+pub struct KeyBindings {
+    app: BindingsLayout<AppAction>,
+    terminal: BindingsLayout,
+    leader: Option<KeyboardShortcut>,
+    leader_actions: BindingsLayout<AppAction>,
+    pub leader_timeout: Duration,
+    pub block_selection: Modifiers, // Held while dragging to select a rectangle
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self::from_config(&Config::default()).expect("default key bindings are valid")
+    }
+}
+
+impl KeyBindings {
+    /// Build the tables from the `[keybindings]` and `[leader]` config
+    /// sections; a listed action replaces its default keys and an empty
+    /// string unbinds it
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let mut app = BindingsLayout::empty();
+        app.add_bindings(Self::configured_bindings(default_app_bindings(), &config.keybindings, "keybindings")?);
+
+        let LeaderConfig { key, timeout_ms, keybindings } = &config.leader;
+        let leader = key.as_deref()
+            .map(|value| parse_shortcut(value).map_err(|e| format!("leader.key: {}", e)))
+            .transpose()?;
+        let mut leader_actions = BindingsLayout::empty();
+        leader_actions.add_bindings(Self::configured_bindings(default_leader_bindings(), keybindings, "leader.keybindings")?);
+
+        let block_selection = parse_modifiers(&config.mouse.block_selection)
+            .map_err(|e| format!("mouse.block_selection: {}", e))?;
+
+        Ok(Self {
+            app,
+            terminal: BindingsLayout::new(),
+            leader,
+            leader_actions,
+            leader_timeout: Duration::from_millis(*timeout_ms),
+            block_selection,
+        })
+    }
+
+    fn configured_bindings(
+        mut bindings: Vec<(KeyboardBinding, AppAction)>,
+        table: &BTreeMap<String, String>,
+        section: &str,
+    ) -> Result<Vec<(KeyboardBinding, AppAction)>, String> {
+        for (name, value) in table {
+            let action = AppAction::from_name(name)
+                .ok_or_else(|| format!("{}: unknown action `{}`", section, name))?;
+            bindings.retain(|(_, bound)| *bound != action);
+
+            if !value.trim().is_empty() {
+                let shortcut = parse_shortcut(value)
+                    .map_err(|e| format!("{}.{}: {}", section, name, e))?;
+                bindings.push((key_binding(shortcut), action));
+            }
+        }
+        Ok(bindings)
+    }
+
+    /// Decide whether a key press is an app action or terminal input
+    pub fn resolve(&self, key: Key, modifiers: Modifiers, terminal_mode: TerminalMode) -> Option<KeyInput> {
+        if let Some(action) = self.app.find_action(&InputKind::KeyCode(key), modifiers, terminal_mode) {
+            return Some(KeyInput::App(*action));
+        }
+        self.resolve_terminal(key, modifiers, terminal_mode).map(KeyInput::Terminal)
+    }
+
+    /// Terminal input for a key, ignoring application bindings
+    pub fn resolve_terminal(&self, key: Key, modifiers: Modifiers, terminal_mode: TerminalMode) -> Option<BindingAction> {
+        match self.terminal.get_action(InputKind::KeyCode(key), modifiers, terminal_mode) {
+            BindingAction::Ignore => None,
+            action => Some(action),
+        }
+    }
+
+    /// First shortcut bound to an action, for showing next to its name
+    pub fn shortcut(&self, action: AppAction) -> Option<KeyboardShortcut> {
+        self.app.bindings().iter()
+            .find(|(_, bound)| *bound == action)
+            .and_then(|(binding, _)| match binding.target {
+                InputKind::KeyCode(key) => Some(KeyboardShortcut::new(binding.modifiers, key)),
+                _ => None,
+            })
+    }
+
+    /// The configured leader shortcut, if leader mode is enabled
+    pub fn leader(&self) -> Option<KeyboardShortcut> {
+        self.leader
+    }
+
+    pub fn is_leader(&self, key: Key, modifiers: Modifiers) -> bool {
+        self.leader.is_some_and(|leader| leader.logical_key == key && modifiers.matches_exact(leader.modifiers))
+    }
+
+    /// Action for the key pressed while the leader is armed
+    pub fn resolve_leader(&self, key: Key, modifiers: Modifiers) -> Option<AppAction> {
+        self.leader_actions
+            .find_action(&InputKind::KeyCode(key), modifiers, TerminalMode::empty())
+            .copied()
+    }
+}
+
+fn key_binding(shortcut: KeyboardShortcut) -> KeyboardBinding {
+    KeyboardBinding {
+        target: InputKind::KeyCode(shortcut.logical_key),
+        modifiers: shortcut.modifiers,
+        terminal_mode_include: TerminalMode::empty(),
+        terminal_mode_exclude: TerminalMode::empty(),
+    }
+}
+
+fn default_app_bindings() -> Vec<(KeyboardBinding, AppAction)> {
+    let mut bindings = default_platform_bindings();
+    bindings.extend(generate_bindings!(
+        KeyboardBinding;
+        B,          Modifiers::COMMAND | Modifiers::SHIFT; AppAction::ToggleBroadcast;
+        A,          Modifiers::COMMAND | Modifiers::SHIFT; AppAction::ToggleAllBroadcast;
+        ArrowLeft,  Modifiers::ALT;                       AppAction::FocusPane(FocusDirection::Left);
+        ArrowRight, Modifiers::ALT;                       AppAction::FocusPane(FocusDirection::Right);
+        ArrowUp,    Modifiers::ALT;                       AppAction::FocusPane(FocusDirection::Up);
+        ArrowDown,  Modifiers::ALT;                       AppAction::FocusPane(FocusDirection::Down);
+        E,          Modifiers::COMMAND | Modifiers::SHIFT; AppAction::EqualizePanes;
+        Z,          Modifiers::COMMAND | Modifiers::SHIFT; AppAction::ToggleZoom;
+        X,          Modifiers::COMMAND | Modifiers::SHIFT; AppAction::ClosePane;
+        Tab,        Modifiers::CTRL;                      AppAction::NextTab;
+        Tab,        Modifiers::CTRL | Modifiers::SHIFT;    AppAction::PreviousTab;
+        PageUp,     Modifiers::COMMAND | Modifiers::SHIFT; AppAction::MoveTabLeft;
+        PageDown,   Modifiers::COMMAND | Modifiers::SHIFT; AppAction::MoveTabRight;
+        F,          Modifiers::COMMAND | Modifiers::SHIFT; AppAction::Find;
+        Space,      Modifiers::COMMAND | Modifiers::SHIFT; AppAction::CopyMode;
+        P,          Modifiers::COMMAND | Modifiers::SHIFT; AppAction::CommandPalette;
+        Plus,       Modifiers::COMMAND;                   AppAction::IncreaseFontSize;
+        Plus,       Modifiers::COMMAND | Modifiers::SHIFT; AppAction::IncreaseFontSize;
+        Equals,     Modifiers::COMMAND;                   AppAction::IncreaseFontSize;
+        Minus,      Modifiers::COMMAND;                   AppAction::DecreaseFontSize;
+        Num0,       Modifiers::COMMAND;                   AppAction::ResetFontSize;
+        Plus,       Modifiers::COMMAND | Modifiers::ALT;   AppAction::IncreaseGlobalFontSize;
+        Plus,       Modifiers::COMMAND | Modifiers::ALT | Modifiers::SHIFT; AppAction::IncreaseGlobalFontSize;
+        Equals,     Modifiers::COMMAND | Modifiers::ALT;   AppAction::IncreaseGlobalFontSize;
+        Minus,      Modifiers::COMMAND | Modifiers::ALT;   AppAction::DecreaseGlobalFontSize;
+        Num0,       Modifiers::COMMAND | Modifiers::ALT;   AppAction::ResetGlobalFontSize;
+        Num1,       Modifiers::COMMAND;                   AppAction::SwitchToTab(0);
+        Num2,       Modifiers::COMMAND;                   AppAction::SwitchToTab(1);
+        Num3,       Modifiers::COMMAND;                   AppAction::SwitchToTab(2);
+        Num4,       Modifiers::COMMAND;                   AppAction::SwitchToTab(3);
+        Num5,       Modifiers::COMMAND;                   AppAction::SwitchToTab(4);
+        Num6,       Modifiers::COMMAND;                   AppAction::SwitchToTab(5);
+        Num7,       Modifiers::COMMAND;                   AppAction::SwitchToTab(6);
+        Num8,       Modifiers::COMMAND;                   AppAction::SwitchToTab(7);
+        Num9,       Modifiers::COMMAND;                   AppAction::SwitchToTab(8);
+    ));
+    bindings
+}
+
+/// Tab, split and resize keys. Cmd+T/W/D/S never reach the shell on macOS;
+/// elsewhere Cmd is Ctrl, and Ctrl+W, Ctrl+D and Ctrl+S belong to readline,
+/// EOF and XOFF, so these add Shift. Ctrl+Shift+Arrow selects words in
+/// terminal editors, so resizing uses Alt+Shift+Arrow there
+#[cfg(target_os = "macos")]
+fn default_platform_bindings() -> Vec<(KeyboardBinding, AppAction)> {
+    generate_bindings!(
+        KeyboardBinding;
+        T,          Modifiers::COMMAND;                   AppAction::NewTab;
+        W,          Modifiers::COMMAND;                   AppAction::CloseTab;
+        D,          Modifiers::COMMAND;                   AppAction::SplitVertical;
+        D,          Modifiers::COMMAND | Modifiers::SHIFT; AppAction::SplitHorizontal;
+        S,          Modifiers::COMMAND;                   AppAction::ToggleGrid;
+        ArrowLeft,  Modifiers::COMMAND | Modifiers::SHIFT; AppAction::ResizePane(FocusDirection::Left);
+        ArrowRight, Modifiers::COMMAND | Modifiers::SHIFT; AppAction::ResizePane(FocusDirection::Right);
+        ArrowUp,    Modifiers::COMMAND | Modifiers::SHIFT; AppAction::ResizePane(FocusDirection::Up);
+        ArrowDown,  Modifiers::COMMAND | Modifiers::SHIFT; AppAction::ResizePane(FocusDirection::Down);
+    )
+}
+
+#[cfg(not(target_os = "macos"))]
+fn default_platform_bindings() -> Vec<(KeyboardBinding, AppAction)> {
+    generate_bindings!(
+        KeyboardBinding;
+        T,          Modifiers::COMMAND | Modifiers::SHIFT;                   AppAction::NewTab;
+        W,          Modifiers::COMMAND | Modifiers::SHIFT;                   AppAction::CloseTab;
+        D,          Modifiers::COMMAND | Modifiers::SHIFT;                   AppAction::SplitVertical;
+        D,          Modifiers::COMMAND | Modifiers::ALT | Modifiers::SHIFT; AppAction::SplitHorizontal;
+        S,          Modifiers::COMMAND | Modifiers::SHIFT;                   AppAction::ToggleGrid;
+        ArrowLeft,  Modifiers::ALT | Modifiers::SHIFT;                       AppAction::ResizePane(FocusDirection::Left);
+        ArrowRight, Modifiers::ALT | Modifiers::SHIFT;                       AppAction::ResizePane(FocusDirection::Right);
+        ArrowUp,    Modifiers::ALT | Modifiers::SHIFT;                       AppAction::ResizePane(FocusDirection::Up);
+        ArrowDown,  Modifiers::ALT | Modifiers::SHIFT;                       AppAction::ResizePane(FocusDirection::Down);
+    )
+}
+
+/// Keys accepted after the leader, loosely following tmux
+fn default_leader_bindings() -> Vec<(KeyboardBinding, AppAction)> {
+    generate_bindings!(
+        KeyboardBinding;
+        C,          Modifiers::NONE; AppAction::NewTab;
+        Backslash,  Modifiers::NONE; AppAction::SplitVertical;
+        Minus,      Modifiers::NONE; AppAction::SplitHorizontal;
+        X,          Modifiers::NONE; AppAction::ClosePane;
+        Z,          Modifiers::NONE; AppAction::ToggleZoom;
+        O,          Modifiers::NONE; AppAction::FocusNextPane;
+        N,          Modifiers::NONE; AppAction::NextTab;
+        P,          Modifiers::NONE; AppAction::PreviousTab;
+        ArrowLeft,  Modifiers::NONE; AppAction::FocusPane(FocusDirection::Left);
+        ArrowRight, Modifiers::NONE; AppAction::FocusPane(FocusDirection::Right);
+        ArrowUp,    Modifiers::NONE; AppAction::FocusPane(FocusDirection::Up);
+        ArrowDown,  Modifiers::NONE; AppAction::FocusPane(FocusDirection::Down);
+        ArrowLeft,  Modifiers::CTRL; AppAction::ResizePane(FocusDirection::Left);
+        ArrowRight, Modifiers::CTRL; AppAction::ResizePane(FocusDirection::Right);
+        ArrowUp,    Modifiers::CTRL; AppAction::ResizePane(FocusDirection::Up);
+        ArrowDown,  Modifiers::CTRL; AppAction::ResizePane(FocusDirection::Down);
+        Equals,     Modifiers::NONE; AppAction::EqualizePanes;
+        ArrowLeft,  Modifiers::SHIFT; AppAction::SwapPane(FocusDirection::Left);
+        ArrowRight, Modifiers::SHIFT; AppAction::SwapPane(FocusDirection::Right);
+        ArrowUp,    Modifiers::SHIFT; AppAction::SwapPane(FocusDirection::Up);
+        ArrowDown,  Modifiers::SHIFT; AppAction::SwapPane(FocusDirection::Down);
+        R,          Modifiers::NONE;  AppAction::RotateSplit;
+        F,          Modifiers::NONE;  AppAction::FlipSplit;
+        T,          Modifiers::NONE;  AppAction::BreakPane;
+        Comma,      Modifiers::NONE;  AppAction::RenameTab;
+        Slash,      Modifiers::NONE;  AppAction::Find;
+        OpenBracket, Modifiers::NONE; AppAction::CopyMode;
+        G,          Modifiers::NONE; AppAction::ToggleGrid;
+        B,          Modifiers::NONE; AppAction::ToggleBroadcast;
+        Num1,       Modifiers::NONE; AppAction::SwitchToTab(0);
+        Num2,       Modifiers::NONE; AppAction::SwitchToTab(1);
+        Num3,       Modifiers::NONE; AppAction::SwitchToTab(2);
+        Num4,       Modifiers::NONE; AppAction::SwitchToTab(3);
+        Num5,       Modifiers::NONE; AppAction::SwitchToTab(4);
+        Num6,       Modifiers::NONE; AppAction::SwitchToTab(5);
+        Num7,       Modifiers::NONE; AppAction::SwitchToTab(6);
+        Num8,       Modifiers::NONE; AppAction::SwitchToTab(7);
+        Num9,       Modifiers::NONE; AppAction::SwitchToTab(8);
+    )
+}
+
+/// Parse a shortcut such as `Ctrl+Shift+T`
+///
+/// `Ctrl`, `Cmd` and `CmdOrCtrl` map to egui's ctrl, mac_cmd and command
+/// modifiers, so `CmdOrCtrl+T` is Cmd+T on macOS and Ctrl+T elsewhere.
+pub fn parse_shortcut(value: &str) -> Result<KeyboardShortcut, String> {
+    let mut modifiers = Modifiers::NONE;
+    let mut key = None;
+
+    for token in value.split('+').map(str::trim) {
+        if key.is_some() {
+            return Err(format!("`{}`: the key must come last", value));
+        }
+        match parse_modifier(token) {
+            Some(modifier) => modifiers |= modifier,
+            None => {
+                let name = if token.len() == 1 {
+                    token.to_ascii_uppercase()
+                } else {
+                    token.to_string()
+                };
+                key = Some(Key::from_name(&name).ok_or_else(|| format!("`{}`: unknown key `{}`", value, token))?);
+            }
+        }
+    }
+
+    key.map(|key| KeyboardShortcut::new(modifiers, key))
+        .ok_or_else(|| format!("`{}`: missing key", value))
+}
+
+/// Parse modifiers without a key, such as `Ctrl+Alt`; an empty string is no modifiers
+pub fn parse_modifiers(value: &str) -> Result<Modifiers, String> {
+    if value.trim().is_empty() {
+        return Ok(Modifiers::NONE);
+    }
+    value.split('+').map(str::trim).try_fold(Modifiers::NONE, |modifiers, token| {
+        parse_modifier(token)
+            .map(|modifier| modifiers | modifier)
+            .ok_or_else(|| format!("`{}`: unknown modifier `{}`", value, token))
+    })
+}
+
+fn parse_modifier(token: &str) -> Option<Modifiers> {
+    match token.to_ascii_lowercase().as_str() {
+        "ctrl" | "control" => Some(Modifiers::CTRL),
+        "cmd" | "command" | "super" => Some(Modifiers::MAC_CMD),
+        "cmdorctrl" => Some(Modifiers::COMMAND),
+        "alt" | "option" => Some(Modifiers::ALT),
+        "shift" => Some(Modifiers::SHIFT),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(bindings: &KeyBindings, modifiers: Modifiers, key: Key) -> Option<KeyInput> {
+        bindings.resolve(key, modifiers, TerminalMode::empty())
+    }
+
+    /// Ctrl as reported by egui on Linux and Windows
+    fn ctrl() -> Modifiers {
+        Modifiers::CTRL | Modifiers::COMMAND
+    }
+
+    #[test]
+    fn test_parse_shortcut() {
+        assert_eq!(
+            parse_shortcut("CmdOrCtrl+Shift+d").unwrap(),
+            KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::D)
+        );
+        assert_eq!(
+            parse_shortcut("Alt+ArrowLeft").unwrap(),
+            KeyboardShortcut::new(Modifiers::ALT, Key::ArrowLeft)
+        );
+        assert_eq!(parse_shortcut("F11").unwrap(), KeyboardShortcut::new(Modifiers::NONE, Key::F11));
+        assert!(parse_shortcut("Ctrl+").is_err());
+        assert_eq!(parse_modifiers("Ctrl+Alt").unwrap(), Modifiers::CTRL | Modifiers::ALT);
+        assert_eq!(parse_modifiers("").unwrap(), Modifiers::NONE);
+        assert!(parse_modifiers("Alt+T").is_err());
+        assert!(parse_shortcut("T+Ctrl").is_err());
+    }
+
+    #[test]
+    fn test_app_bindings_take_precedence() {
+        let bindings = KeyBindings::default();
+        assert_eq!(resolve(&bindings, ctrl(), Key::Num3), Some(KeyInput::App(AppAction::SwitchToTab(2))));
+        assert_eq!(resolve(&bindings, ctrl(), Key::U), Some(KeyInput::Terminal(BindingAction::Char('\x15'))));
+        assert_eq!(resolve(&bindings, Modifiers::NONE, Key::A), None);
+        assert_eq!(resolve(&bindings, ctrl(), Key::Tab), Some(KeyInput::App(AppAction::NextTab)));
+        assert_eq!(resolve(&bindings, ctrl() | Modifiers::SHIFT, Key::Tab), Some(KeyInput::App(AppAction::PreviousTab)));
+        assert_eq!(resolve(&bindings, Modifiers::NONE, Key::Tab), Some(KeyInput::Terminal(BindingAction::Char('\x09'))));
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn test_shell_control_keys_reach_terminal() {
+        let bindings = KeyBindings::default();
+        for (key, byte) in [(Key::W, '\x17'), (Key::D, '\x04'), (Key::S, '\x13'), (Key::T, '\x14')] {
+            assert_eq!(resolve(&bindings, ctrl(), key), Some(KeyInput::Terminal(BindingAction::Char(byte))));
+        }
+        let ctrl_shift = ctrl() | Modifiers::SHIFT;
+        assert_eq!(resolve(&bindings, ctrl_shift, Key::T), Some(KeyInput::App(AppAction::NewTab)));
+        assert_eq!(resolve(&bindings, ctrl_shift, Key::W), Some(KeyInput::App(AppAction::CloseTab)));
+        assert_eq!(resolve(&bindings, ctrl_shift, Key::D), Some(KeyInput::App(AppAction::SplitVertical)));
+        assert_eq!(resolve(&bindings, ctrl_shift | Modifiers::ALT, Key::D), Some(KeyInput::App(AppAction::SplitHorizontal)));
+        assert_eq!(resolve(&bindings, ctrl_shift, Key::S), Some(KeyInput::App(AppAction::ToggleGrid)));
+
+        // Ctrl+Shift+Arrow selects words in terminal editors
+        assert!(matches!(resolve(&bindings, ctrl_shift, Key::ArrowLeft), Some(KeyInput::Terminal(_))));
+        assert_eq!(
+            resolve(&bindings, Modifiers::ALT | Modifiers::SHIFT, Key::ArrowLeft),
+            Some(KeyInput::App(AppAction::ResizePane(FocusDirection::Left)))
+        );
+    }
+
+    #[test]
+    fn test_font_zoom_bindings() {
+        let bindings = KeyBindings::default();
+        for (modifiers, key) in [(ctrl(), Key::Plus), (ctrl() | Modifiers::SHIFT, Key::Plus), (ctrl(), Key::Equals)] {
+            assert_eq!(resolve(&bindings, modifiers, key), Some(KeyInput::App(AppAction::IncreaseFontSize)));
+        }
+        assert_eq!(resolve(&bindings, ctrl() | Modifiers::ALT, Key::Minus), Some(KeyInput::App(AppAction::DecreaseGlobalFontSize)));
+        assert_eq!(
+            bindings.shortcut(AppAction::ResetFontSize),
+            Some(KeyboardShortcut::new(Modifiers::COMMAND, Key::Num0))
+        );
+        assert_eq!(bindings.shortcut(AppAction::RenameTab), None);
+    }
+
+    #[test]
+    fn test_unbound_action_goes_to_terminal() {
+        let config = Config {
+            keybindings: BTreeMap::from([
+                ("split_vertical".to_string(), String::new()),
+                ("toggle_grid".to_string(), "Ctrl+Shift+G".to_string()),
+            ]),
+            ..Config::default()
+        };
+        let bindings = KeyBindings::from_config(&config).unwrap();
+
+        assert_eq!(resolve(&bindings, ctrl(), Key::D), Some(KeyInput::Terminal(BindingAction::Char('\x04'))));
+        assert_eq!(resolve(&bindings, ctrl(), Key::S), Some(KeyInput::Terminal(BindingAction::Char('\x13'))));
+        assert_eq!(
+            resolve(&bindings, ctrl() | Modifiers::SHIFT, Key::G),
+            Some(KeyInput::App(AppAction::ToggleGrid))
+        );
+    }
+
+    #[test]
+    fn test_unknown_action_is_an_error() {
+        let mut config = Config::default();
+        config.keybindings.insert("fly".to_string(), "Ctrl+F".to_string());
+        assert!(KeyBindings::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_leader_is_off_by_default() {
+        let bindings = KeyBindings::default();
+        assert_eq!(bindings.leader(), None);
+        assert!(!bindings.is_leader(Key::A, ctrl()));
+        assert_eq!(bindings.resolve_leader(Key::Minus, Modifiers::NONE), Some(AppAction::SplitHorizontal));
+    }
+}
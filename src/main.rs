@@ -2,11 +2,22 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
 mod app;
+mod action_manager;
 mod types;
 mod tab_manager;
 mod split_manager;
 mod grid_manager;
 mod broadcast_manager;
+mod config_manager;
+mod keybindings;
+mod session_manager;
+mod search_manager;
+mod export_manager;
+mod copy_mode_manager;
+mod palette_manager;
+mod font_manager;
+mod theme_manager;
+mod theme_import;
 mod input_handler;
 mod ui_renderer;
 mod ime;
@@ -0,0 +1,204 @@
+use crate::types::AppState;
+use crate::action_manager::{ActionManager, AppAction, NAMED_ACTIONS};
+use crate::config_manager::collapse_home;
+use crate::tab_manager::TabManager;
+use crate::split_manager::SplitManager;
+use crate::theme_manager::ThemeManager;
+
+/// Ctrl+Shift+P overlay listing actions, tabs and panes
+#[derive(Debug, Clone, Default)]
+pub struct CommandPalette {
+    pub query: String,
+    pub selected: usize, // Index into the filtered entries
+    pub entries: Vec<PaletteEntry>, // Listed when the palette opens, filtered every frame
+}
+
+/// What running a palette entry does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteTarget {
+    Action(AppAction),
+    Tab(u64),  // tab_id
+    Pane(u64), // terminal_id
+    Theme(&'static str),
+}
+
+#[derive(Debug, Clone)]
+pub struct PaletteEntry {
+    pub label: String,
+    pub detail: String, // Shortcut of an action, directory of a tab or pane
+    pub target: PaletteTarget,
+}
+
+pub struct PaletteManager;
+
+impl PaletteManager {
+    /// Open the palette, or close it when it is already open
+    pub fn toggle(state: &mut AppState) {
+        state.command_palette = match state.command_palette {
+            Some(_) => None,
+            None => {
+                let ctx = state.egui_ctx.clone();
+                Some(CommandPalette { entries: Self::entries(state, &ctx), ..CommandPalette::default() })
+            }
+        };
+    }
+    
+    /// Entries matching the query, best match first
+    pub fn matches(state: &AppState) -> Vec<PaletteEntry> {
+        let Some(palette) = &state.command_palette else {
+            return Vec::new();
+        };
+        let mut scored: Vec<(u32, PaletteEntry)> = palette.entries.iter()
+            .filter_map(|entry| Some((Self::fuzzy_score(&palette.query, &entry.label)?, entry.clone())))
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score)); // Stable, so ties keep the listing order
+        scored.into_iter().map(|(_, entry)| entry).collect()
+    }
+    
+    /// Move the highlighted entry, wrapping around
+    pub fn move_selection(state: &mut AppState, offset: isize, count: usize) {
+        if let Some(palette) = &mut state.command_palette {
+            if count > 0 {
+                palette.selected = (palette.selected as isize + offset).rem_euclid(count as isize) as usize;
+            }
+        }
+    }
+    
+    /// Close the palette and run the entry the same way its shortcut would
+    pub fn run(state: &mut AppState, target: PaletteTarget) {
+        state.command_palette = None;
+        match target {
+            PaletteTarget::Action(action) => ActionManager::dispatch(state, action),
+            PaletteTarget::Tab(tab_id) => TabManager::switch_to_tab(state, tab_id),
+            PaletteTarget::Theme(name) => ThemeManager::select(state, name),
+            PaletteTarget::Pane(terminal_id) => {
+                if let Some(tab_id) = TabManager::find_tab_of_terminal(state, terminal_id) {
+                    TabManager::switch_to_tab(state, tab_id);
+                    state.focused_terminal = Some(terminal_id);
+                    SplitManager::record_focus(state);
+                }
+            }
+        }
+    }
+    
+    /// Every action, then the bundled themes, the open tabs and the panes of split tabs
+    fn entries(state: &AppState, ctx: &egui::Context) -> Vec<PaletteEntry> {
+        let mut entries: Vec<PaletteEntry> = NAMED_ACTIONS.iter()
+            .filter(|(_, action)| *action != AppAction::CommandPalette)
+            .map(|&(name, action)| PaletteEntry {
+                label: Self::action_label(name),
+                detail: state.key_bindings.shortcut(action)
+                    .map(|shortcut| ctx.format_shortcut(&shortcut))
+                    .unwrap_or_default(),
+                target: PaletteTarget::Action(action),
+            })
+            .collect();
+        
+        entries.extend(ThemeManager::theme_names().map(|name| PaletteEntry {
+            label: format!("Theme: {}", name),
+            detail: if name == state.theme_name { String::from("current") } else { String::new() },
+            target: PaletteTarget::Theme(name),
+        }));
+        
+        let directory = |terminal_id: u64| {
+            state.terminals.get(&terminal_id)
+                .and_then(|terminal| terminal.working_directory())
+                .map(|path| collapse_home(&path))
+                .unwrap_or_default()
+        };
+        
+        for &tab_id in &state.tab_order {
+            let Some(tab) = state.tabs.get(&tab_id) else {
+                continue;
+            };
+            let title = TabManager::display_title(state, tab_id, tab);
+            entries.push(PaletteEntry {
+                label: format!("Tab: {}", title),
+                detail: TabManager::last_used_terminal(state, tab_id).map(directory).unwrap_or_default(),
+                target: PaletteTarget::Tab(tab_id),
+            });
+            
+            let terminal_ids = state.tab_layouts.get(&tab_id)
+                .map(TabManager::collect_terminal_ids)
+                .unwrap_or_default();
+            if terminal_ids.len() < 2 {
+                continue;
+            }
+            for terminal_id in terminal_ids {
+                let pane_title = state.terminal_titles.get(&terminal_id)
+                    .cloned()
+                    .unwrap_or_else(|| format!("Terminal {}", terminal_id));
+                entries.push(PaletteEntry {
+                    label: format!("Pane: {} ({})", pane_title, title),
+                    detail: directory(terminal_id),
+                    target: PaletteTarget::Pane(terminal_id),
+                });
+            }
+        }
+        entries
+    }
+    
+    /// `split_vertical` -> "Split vertical"
+    fn action_label(name: &str) -> String {
+        let words = name.replace('_', " ");
+        let mut chars = words.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => words,
+        }
+    }
+    
+    /// Score `text` against a query whose characters must appear in order,
+    /// case-insensitively; runs and word starts score higher. `None` means no match.
+    fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+        let mut query_chars = query.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).peekable();
+        let mut score = 0;
+        let mut previous: Option<char> = None;
+        let mut previous_matched = false;
+        
+        for c in text.chars() {
+            let Some(&wanted) = query_chars.peek() else {
+                break;
+            };
+            let matched = c.to_lowercase().eq(std::iter::once(wanted));
+            if matched {
+                query_chars.next();
+                score += 1;
+                if previous_matched {
+                    score += 3;
+                }
+                if previous.is_none_or(|p| !p.is_alphanumeric()) {
+                    score += 2;
+                }
+            }
+            previous_matched = matched;
+            previous = Some(c);
+        }
+        
+        query_chars.peek().is_none().then_some(score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(PaletteManager::fuzzy_score("", "Split vertical"), Some(0));
+        assert!(PaletteManager::fuzzy_score("spv", "Split vertical").is_some());
+        assert_eq!(PaletteManager::fuzzy_score("vs", "Split vertical"), None);
+        assert_eq!(PaletteManager::fuzzy_score("SPLIT", "Split vertical"), PaletteManager::fuzzy_score("split", "Split vertical"));
+
+        // Word starts and runs beat scattered letters
+        let word_start = PaletteManager::fuzzy_score("zoom", "Toggle zoom").unwrap();
+        let scattered = PaletteManager::fuzzy_score("zoom", "Resize pane to bottom").unwrap_or(0);
+        assert!(word_start > scattered);
+    }
+
+    #[test]
+    fn test_action_label() {
+        assert_eq!(PaletteManager::action_label("split_vertical"), "Split vertical");
+        assert_eq!(PaletteManager::action_label("find"), "Find");
+    }
+}
@@ -0,0 +1,55 @@
+use crate::types::AppState;
+use egui_term::SearchOptions;
+
+/// Find bar of one pane
+#[derive(Debug, Clone, Default)]
+pub struct PaneSearch {
+    pub query: String,
+    pub options: SearchOptions,
+    pub error: Option<String>, // Set when the query is not a valid regex
+}
+
+pub struct SearchManager;
+
+impl SearchManager {
+    /// Open the find bar on the focused pane, or move the keyboard back to it
+    pub fn open_find_bar(state: &mut AppState) {
+        if let Some(terminal_id) = state.focused_terminal {
+            state.pane_searches.entry(terminal_id).or_default();
+            state.egui_ctx.memory_mut(|memory| memory.request_focus(Self::input_id(terminal_id)));
+        }
+    }
+    
+    /// Close a pane's find bar and drop its highlights
+    pub fn close_find_bar(state: &mut AppState, terminal_id: u64) {
+        state.pane_searches.remove(&terminal_id);
+        if let Some(terminal) = state.terminals.get_mut(&terminal_id) {
+            terminal.clear_search();
+        }
+    }
+    
+    /// Search again after the query or an option changed
+    pub fn update_search(state: &mut AppState, terminal_id: u64) {
+        if let (Some(search), Some(terminal)) = (state.pane_searches.get_mut(&terminal_id), state.terminals.get_mut(&terminal_id)) {
+            search.error = terminal.search(&search.query, search.options).err();
+        }
+    }
+    
+    /// Jump to the next match below (`forward`) or above the current one
+    pub fn step(state: &mut AppState, terminal_id: u64, forward: bool) {
+        if let Some(terminal) = state.terminals.get_mut(&terminal_id) {
+            terminal.search_step(forward);
+        }
+    }
+    
+    /// True while a find bar's text field has the keyboard
+    pub fn has_keyboard(state: &AppState, ctx: &egui::Context) -> bool {
+        ctx.memory(|memory| memory.focused())
+            .is_some_and(|focused| state.pane_searches.keys().any(|&terminal_id| Self::input_id(terminal_id) == focused))
+    }
+    
+    /// Widget id of a pane's search field
+    pub fn input_id(terminal_id: u64) -> egui::Id {
+        egui::Id::new(("find_bar", terminal_id))
+    }
+}
@@ -0,0 +1,225 @@
+use crate::grid_manager::GridManager;
+use crate::tab_manager::TabManager;
+use crate::types::{AppState, PanelContent, TerminalTab, ViewMode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Storage key used with eframe's persistence
+const SESSION_KEY: &str = "tterm_session";
+
+/// Everything needed to rebuild the window layout on relaunch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub tabs: HashMap<u64, TerminalTab>,
+    pub tab_order: Vec<u64>,
+    pub active_tab_id: u64,
+    pub tab_layouts: HashMap<u64, PanelContent>,
+    pub view_mode: ViewMode,
+    /// Saved terminal id -> pane state
+    pub panes: HashMap<u64, PaneSnapshot>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PaneSnapshot {
+    pub working_directory: Option<PathBuf>,
+    /// Last lines of output, only captured when `session.scrollback_lines` is set
+    pub scrollback: Option<String>,
+}
+
+pub struct SessionManager;
+
+impl SessionManager {
+    /// Write the current layout to eframe's storage
+    pub fn save(state: &AppState, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, SESSION_KEY, &Self::capture(state));
+    }
+
+    /// Rebuild the saved layout with fresh shells; returns false when there
+    /// was nothing usable to restore
+    pub fn restore(state: &mut AppState, storage: Option<&dyn eframe::Storage>) -> bool {
+        if !state.config.session.restore {
+            return false;
+        }
+
+        let Some(session) = storage.and_then(|s| eframe::get_value::<Session>(s, SESSION_KEY)) else {
+            return false;
+        };
+        if !Self::is_valid(&session) {
+            log::warn!("Ignoring inconsistent saved session");
+            return false;
+        }
+
+        Self::apply(state, session);
+        true
+    }
+
+    /// Snapshot tabs, layouts and per-pane state
+    pub fn capture(state: &AppState) -> Session {
+        let scrollback_lines = state.config.session.scrollback_lines;
+        let panes = state.terminals.iter()
+            .map(|(&terminal_id, backend)| {
+                let scrollback = (scrollback_lines > 0)
+                    .then(|| backend.last_lines_text(scrollback_lines))
+                    .filter(|text| !text.is_empty());
+                let pane = PaneSnapshot {
+                    working_directory: backend.working_directory(),
+                    scrollback,
+                };
+                (terminal_id, pane)
+            })
+            .collect();
+
+        Session {
+            tabs: state.tabs.clone(),
+            tab_order: state.tab_order.clone(),
+            active_tab_id: state.active_tab_id,
+            tab_layouts: state.tab_layouts.clone(),
+            view_mode: state.view_mode.clone(),
+            panes,
+        }
+    }
+
+    /// Every ordered tab needs a title and a layout
+    fn is_valid(session: &Session) -> bool {
+        !session.tab_order.is_empty()
+            && session.tab_order.iter().all(|tab_id| {
+                session.tabs.contains_key(tab_id) && session.tab_layouts.contains_key(tab_id)
+            })
+    }
+
+    fn apply(state: &mut AppState, session: Session) {
+        let Session { mut tabs, tab_order, active_tab_id, mut tab_layouts, view_mode, panes } = session;
+
+        for &tab_id in &tab_order {
+            let (Some(tab), Some(mut layout)) = (tabs.remove(&tab_id), tab_layouts.remove(&tab_id)) else {
+                continue;
+            };
+            Self::spawn_panes(state, &mut layout, &panes);
+            state.tabs.insert(tab_id, tab);
+            state.tab_layouts.insert(tab_id, layout);
+        }
+
+        state.next_tab_id = tab_order.iter().max().map_or(1, |max_id| max_id + 1);
+        state.tab_order = tab_order;
+
+        let active_tab_id = if state.tabs.contains_key(&active_tab_id) {
+            active_tab_id
+        } else {
+            state.tab_order[0]
+        };
+        TabManager::switch_to_tab(state, active_tab_id);
+
+        state.view_mode = view_mode;
+        if !Self::grid_matches_tabs(&state.view_mode, state.tabs.len()) {
+            GridManager::update_grid_size(state);
+        }
+    }
+
+    /// Replace saved terminal ids with newly spawned terminals
+    fn spawn_panes(state: &mut AppState, content: &mut PanelContent, panes: &HashMap<u64, PaneSnapshot>) {
+        match content {
+            PanelContent::Terminal(id) => {
+                let pane = panes.get(id).cloned().unwrap_or_default();
+                let terminal_id = state.create_terminal_with_cwd(pane.working_directory);
+                if let (Some(text), Some(backend)) = (pane.scrollback, state.terminals.get_mut(&terminal_id)) {
+                    backend.replay_output(Self::replay_bytes(&text).as_slice());
+                }
+                *id = terminal_id;
+            }
+            PanelContent::Split { first, second, .. } => {
+                Self::spawn_panes(state, first, panes);
+                Self::spawn_panes(state, second, panes);
+            }
+        }
+    }
+
+    /// Captured text uses bare newlines; the terminal needs CR LF
+    fn replay_bytes(text: &str) -> Vec<u8> {
+        let mut bytes = text.replace('\n', "\r\n").into_bytes();
+        bytes.extend_from_slice(b"\r\n");
+        bytes
+    }
+
+    /// Saved grid ratios are only reused when they still fit the tab count
+    fn grid_matches_tabs(view_mode: &ViewMode, tab_count: usize) -> bool {
+        match view_mode {
+            ViewMode::Single => true,
+            ViewMode::Grid { rows, cols, col_ratios, row_ratios } => {
+                tab_count > 1
+                    && (*rows, *cols) == GridManager::calculate_optimal_grid_size(tab_count)
+                    && col_ratios.len() == *cols
+                    && row_ratios.len() == *rows
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SplitDirection;
+
+    fn sample_session() -> Session {
+        let layout = PanelContent::Split {
+            direction: SplitDirection::Vertical,
+            first: Box::new(PanelContent::Terminal(3)),
+            second: Box::new(PanelContent::Terminal(4)),
+            ratio: 0.3,
+        };
+        Session {
+            tabs: HashMap::from([(2, TerminalTab::new(2))]),
+            tab_order: vec![2],
+            active_tab_id: 2,
+            tab_layouts: HashMap::from([(2, layout)]),
+            view_mode: ViewMode::Single,
+            panes: HashMap::from([(3, PaneSnapshot {
+                working_directory: Some(PathBuf::from("/tmp")),
+                scrollback: None,
+            })]),
+        }
+    }
+
+    #[test]
+    fn test_session_round_trip() {
+        let text = ron::to_string(&sample_session()).unwrap();
+        let session: Session = ron::from_str(&text).unwrap();
+
+        assert!(SessionManager::is_valid(&session));
+        match &session.tab_layouts[&2] {
+            PanelContent::Split { direction: SplitDirection::Vertical, ratio, .. } => assert_eq!(*ratio, 0.3),
+            other => panic!("unexpected layout {:?}", other),
+        }
+        assert_eq!(session.panes[&3].working_directory, Some(PathBuf::from("/tmp")));
+    }
+
+    #[test]
+    fn test_tabs_saved_before_titles_and_colors_load() {
+        let tab: TerminalTab = ron::from_str(r#"(id: 2, title: "Terminal 2")"#).unwrap();
+        assert_eq!(tab.pinned_title, None);
+        assert_eq!(tab.color, None);
+    }
+
+    #[test]
+    fn test_inconsistent_session_is_rejected() {
+        let mut session = sample_session();
+        session.tab_order.push(7);
+        assert!(!SessionManager::is_valid(&session));
+
+        session.tab_order.clear();
+        assert!(!SessionManager::is_valid(&session));
+    }
+
+    #[test]
+    fn test_grid_ratios_must_fit_tab_count() {
+        let grid = ViewMode::Grid { rows: 1, cols: 2, col_ratios: vec![0.7, 0.3], row_ratios: vec![1.0] };
+        assert!(SessionManager::grid_matches_tabs(&grid, 2));
+        assert!(!SessionManager::grid_matches_tabs(&grid, 4));
+        assert!(SessionManager::grid_matches_tabs(&ViewMode::Single, 4));
+    }
+
+    #[test]
+    fn test_replay_bytes_use_crlf() {
+        assert_eq!(SessionManager::replay_bytes("a\nb"), b"a\r\nb\r\n".to_vec());
+    }
+}
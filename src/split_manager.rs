@@ -1,11 +1,25 @@
-use crate::types::{AppState, PanelContent, SplitDirection};
+use crate::types::{AppState, FocusDirection, PanelContent, SplitDirection};
 use crate::tab_manager::TabManager;
+use egui::Rect;
+
+/// Slack for pane edges that touch or overlap by rounding
+const EDGE_TOLERANCE: f32 = 1.0;
+
+/// Limits for split ratios changed by dragging or resizing
+pub const MIN_SPLIT_RATIO: f32 = 0.1;
+pub const MAX_SPLIT_RATIO: f32 = 0.9;
+
+/// Ratio change per keyboard resize step
+const RESIZE_STEP: f32 = 0.05;
 
 pub struct SplitManager;
 
 impl SplitManager {
     /// Split the focused panel in the specified direction
     pub fn split_focused_panel(state: &mut AppState, direction: SplitDirection) {
+        // The new pane should be visible, so splitting leaves zoom
+        state.zoomed_panes.remove(&state.active_tab_id);
+        
         if let Some(focused_terminal_id) = state.focused_terminal {
             let new_terminal_id = state.create_terminal();
             let active_tab_id = state.active_tab_id;
@@ -45,16 +59,71 @@ impl SplitManager {
         }
     }
     
+    /// Move every pane of `source_tab` into the tab holding `target`, split off
+    /// beside it, and drop the emptied tab; the terminals keep running
+    pub fn join_tab(state: &mut AppState, source_tab: u64, target: u64) -> bool {
+        let Some(target_tab) = TabManager::find_tab_of_terminal(state, target) else {
+            return false;
+        };
+        if source_tab == target_tab {
+            return false;
+        }
+        let Some(source_layout) = state.tab_layouts.remove(&source_tab) else {
+            return false;
+        };
+        let Some(anchor) = TabManager::get_first_terminal_id(&source_layout) else {
+            return false;
+        };
+        
+        if let Some(layout) = state.tab_layouts.get_mut(&target_tab) {
+            Self::replace_terminal_with_split_static(layout, target, SplitDirection::Vertical, anchor);
+            // The new split holds the anchor leaf; graft the whole source layout there
+            Self::replace_terminal(layout, anchor, source_layout);
+        }
+        
+        state.tabs.remove(&source_tab);
+        state.tab_order.retain(|&id| id != source_tab);
+        for tab_id in [source_tab, target_tab] {
+            state.zoomed_panes.remove(&tab_id);
+        }
+        crate::grid_manager::GridManager::update_grid_size(state);
+        
+        state.active_tab_id = target_tab;
+        state.focused_terminal = Some(anchor);
+        true
+    }
+    
+    /// Replace the leaf of `terminal_id` with `replacement`
+    fn replace_terminal(content: &mut PanelContent, terminal_id: u64, replacement: PanelContent) -> bool {
+        match content {
+            PanelContent::Terminal(id) if *id == terminal_id => {
+                *content = replacement;
+                true
+            }
+            PanelContent::Split { first, second, .. } => {
+                if Self::contains_terminal(first, terminal_id) {
+                    Self::replace_terminal(first, terminal_id, replacement)
+                } else {
+                    Self::replace_terminal(second, terminal_id, replacement)
+                }
+            }
+            _ => false,
+        }
+    }
+    
+    /// Close a pane and shut down its shell
+    pub fn close_pane(state: &mut AppState, terminal_id: u64) {
+        let ctx = state.egui_ctx.clone();
+        Self::handle_terminal_exit(state, terminal_id, &ctx);
+    }
+    
     /// Handle terminal exit and merge panels if necessary
     pub fn handle_terminal_exit(state: &mut AppState, terminal_id: u64, ctx: &egui::Context) {
         // Find which tab contains this terminal
         let mut tab_to_close = None;
-        let mut terminal_found = false;
         
         for (&tab_id, layout) in &state.tab_layouts {
             if Self::contains_terminal(layout, terminal_id) {
-                terminal_found = true;
-                
                 // Check if this is the only terminal in the tab
                 let terminal_count = Self::count_terminals_in_layout(layout);
                 
@@ -71,7 +140,7 @@ impl SplitManager {
                 } else {
                     // There are other terminals in the tab, find sibling BEFORE removing the terminal
                     let sibling_id = if state.focused_terminal == Some(terminal_id) {
-                        Self::find_sibling_terminal_before_removal(&layout, terminal_id)
+                        Self::find_sibling_terminal_before_removal(layout, terminal_id)
                     } else {
                         None
                     };
@@ -92,20 +161,24 @@ impl SplitManager {
             }
         }
         
-        if !terminal_found {
-            // Terminal not found in any tab layout, just remove from terminals map
-            state.terminals.remove(&terminal_id);
-            state.selected_terminals.remove(&terminal_id);
-            return;
-        }
-        
         if let Some(tab_id) = tab_to_close {
             TabManager::close_tab(state, tab_id);
         }
         
-        // Remove terminal from data structures
+        // Also covers terminals not found in any tab layout
+        Self::forget_terminal(state, terminal_id);
+    }
+    
+    /// Drop everything kept per terminal once its pane is gone
+    pub fn forget_terminal(state: &mut AppState, terminal_id: u64) {
         state.terminals.remove(&terminal_id);
+        state.pane_rects.remove(&terminal_id);
         state.selected_terminals.remove(&terminal_id);
+        state.korean_input_states.remove(&terminal_id);
+        state.pane_font_zoom.remove(&terminal_id);
+        state.terminal_titles.remove(&terminal_id);
+        state.pane_searches.remove(&terminal_id);
+        state.zoomed_panes.retain(|_, zoomed| *zoomed != terminal_id);
     }
     
     /// Check if a layout contains a specific terminal
@@ -168,7 +241,7 @@ impl SplitManager {
     }
     
     /// Remove terminal from layout and return merged layout
-    fn remove_terminal_from_layout(content: &PanelContent, terminal_id: u64) -> Option<PanelContent> {
+    pub fn remove_terminal_from_layout(content: &PanelContent, terminal_id: u64) -> Option<PanelContent> {
         match content {
             PanelContent::Terminal(id) if *id == terminal_id => {
                 // This terminal should be removed, return None to indicate removal
@@ -223,6 +296,10 @@ impl SplitManager {
                     // Move to next terminal (cycling back to first if at end)
                     let next_idx = (current_idx + 1) % terminal_ids.len();
                     state.focused_terminal = Some(terminal_ids[next_idx]);
+                    // Cycling while zoomed keeps the zoom on the newly focused pane
+                    if let Some(zoomed) = state.zoomed_panes.get_mut(&state.active_tab_id) {
+                        *zoomed = terminal_ids[next_idx];
+                    }
                 } else {
                     // If current focus is not in this tab, focus on first terminal
                     state.focused_terminal = terminal_ids.first().copied();
@@ -233,4 +310,528 @@ impl SplitManager {
             }
         }
     }
+    
+    /// Show only the focused pane across its tab, or restore the split layout
+    pub fn toggle_zoom(state: &mut AppState) {
+        let active_tab_id = state.active_tab_id;
+        if state.zoomed_panes.remove(&active_tab_id).is_some() {
+            return;
+        }
+        
+        if let (Some(focused), Some(layout)) = (state.focused_terminal, state.tab_layouts.get(&active_tab_id)) {
+            if Self::count_terminals_in_layout(layout) > 1 && Self::contains_terminal(layout, focused) {
+                state.zoomed_panes.insert(active_tab_id, focused);
+            }
+        }
+    }
+    
+    /// The pane currently zoomed in a tab
+    pub fn zoomed_terminal(state: &AppState, tab_id: u64) -> Option<u64> {
+        state.zoomed_panes.get(&tab_id).copied()
+    }
+    
+    /// Rects of the two sides of a split, leaving room for the 4px separator
+    pub fn split_rects(direction: SplitDirection, ratio: f32, area: Rect) -> (Rect, Rect) {
+        match direction {
+            SplitDirection::Horizontal => {
+                let split_y = area.min.y + area.height() * ratio;
+                (
+                    Rect::from_min_max(area.min, egui::pos2(area.max.x, split_y - 2.0)),
+                    Rect::from_min_max(egui::pos2(area.min.x, split_y + 2.0), area.max),
+                )
+            }
+            SplitDirection::Vertical => {
+                let split_x = area.min.x + area.width() * ratio;
+                (
+                    Rect::from_min_max(area.min, egui::pos2(split_x - 2.0, area.max.y)),
+                    Rect::from_min_max(egui::pos2(split_x + 2.0, area.min.y), area.max),
+                )
+            }
+        }
+    }
+    
+    /// Rect of every terminal in a layout laid out in `area`
+    fn layout_rects(content: &PanelContent, area: Rect, rects: &mut Vec<(u64, Rect)>) {
+        match content {
+            PanelContent::Terminal(id) => rects.push((*id, area)),
+            PanelContent::Split { direction, first, second, ratio } => {
+                let (first_rect, second_rect) = Self::split_rects(*direction, *ratio, area);
+                Self::layout_rects(first, first_rect, rects);
+                Self::layout_rects(second, second_rect, rects);
+            }
+        }
+    }
+    
+    /// Move the border of the nearest enclosing split that runs across
+    /// `direction`, growing or shrinking the focused pane
+    pub fn resize_focused_pane(state: &mut AppState, direction: FocusDirection) {
+        let (orientation, delta) = match direction {
+            FocusDirection::Left => (SplitDirection::Vertical, -RESIZE_STEP),
+            FocusDirection::Right => (SplitDirection::Vertical, RESIZE_STEP),
+            FocusDirection::Up => (SplitDirection::Horizontal, -RESIZE_STEP),
+            FocusDirection::Down => (SplitDirection::Horizontal, RESIZE_STEP),
+        };
+        
+        if let Some(terminal_id) = state.focused_terminal {
+            if let Some(layout) = state.tab_layouts.get_mut(&state.active_tab_id) {
+                Self::adjust_enclosing_ratio(layout, terminal_id, orientation, delta);
+            }
+        }
+    }
+    
+    /// Rebalance every split in the active tab so all panes get the same area
+    pub fn equalize_splits(state: &mut AppState) {
+        if let Some(layout) = state.tab_layouts.get_mut(&state.active_tab_id) {
+            Self::equalize_layout(layout);
+        }
+    }
+    
+    /// Change the ratio of the innermost `orientation` split containing the
+    /// terminal; returns None when the terminal is not in `content`, otherwise
+    /// whether a ratio was changed
+    fn adjust_enclosing_ratio(
+        content: &mut PanelContent,
+        terminal_id: u64,
+        orientation: SplitDirection,
+        delta: f32,
+    ) -> Option<bool> {
+        match content {
+            PanelContent::Terminal(id) => (*id == terminal_id).then_some(false),
+            PanelContent::Split { direction, first, second, ratio } => {
+                let adjusted = Self::adjust_enclosing_ratio(first, terminal_id, orientation, delta)
+                    .or_else(|| Self::adjust_enclosing_ratio(second, terminal_id, orientation, delta))?;
+                
+                if !adjusted && *direction == orientation {
+                    *ratio = (*ratio + delta).clamp(MIN_SPLIT_RATIO, MAX_SPLIT_RATIO);
+                    return Some(true);
+                }
+                Some(adjusted)
+            }
+        }
+    }
+    
+    /// Give each side of a split a share proportional to its pane count, so
+    /// every leaf ends up with 1/n of the area
+    fn equalize_layout(content: &mut PanelContent) {
+        if let PanelContent::Split { first, second, ratio, .. } = content {
+            let first_count = Self::count_terminals_in_layout(first);
+            let second_count = Self::count_terminals_in_layout(second);
+            *ratio = first_count as f32 / (first_count + second_count) as f32;
+            
+            Self::equalize_layout(first);
+            Self::equalize_layout(second);
+        }
+    }
+    
+    /// Move focus to the nearest pane in `direction`, using the pane rects of the
+    /// last frame; in grid view this crosses into neighbouring cells
+    pub fn focus_pane_in_direction(state: &mut AppState, direction: FocusDirection) {
+        let Some(current) = state.focused_terminal else {
+            return;
+        };
+        let rects = Self::navigation_rects(state);
+        
+        if let Some(target) = Self::find_pane_in_direction(&rects, current, direction, &state.focus_history) {
+            if let Some(tab_id) = TabManager::find_tab_of_terminal(state, target) {
+                state.active_tab_id = tab_id;
+                // Moving to a hidden pane leaves zoom
+                if state.zoomed_panes.get(&tab_id).is_some_and(|&zoomed| zoomed != target) {
+                    state.zoomed_panes.remove(&tab_id);
+                }
+            }
+            state.focused_terminal = Some(target);
+        }
+    }
+    
+    /// Swap the focused pane with its neighbour in `direction`; focus moves with it
+    pub fn swap_focused_pane(state: &mut AppState, direction: FocusDirection) {
+        let Some(current) = state.focused_terminal else {
+            return;
+        };
+        let rects = Self::navigation_rects(state);
+        let Some(target) = Self::find_pane_in_direction(&rects, current, direction, &state.focus_history) else {
+            return;
+        };
+        let (Some(current_tab), Some(target_tab)) = (
+            TabManager::find_tab_of_terminal(state, current),
+            TabManager::find_tab_of_terminal(state, target),
+        ) else {
+            return;
+        };
+        
+        for tab_id in [current_tab, target_tab] {
+            state.zoomed_panes.remove(&tab_id);
+        }
+        if let Some(layout) = state.tab_layouts.get_mut(&current_tab) {
+            Self::swap_terminal_ids(layout, current, target);
+        }
+        if target_tab != current_tab {
+            // In grid view the neighbour can live in another tab
+            if let Some(layout) = state.tab_layouts.get_mut(&target_tab) {
+                Self::swap_terminal_ids(layout, current, target);
+            }
+            state.active_tab_id = target_tab;
+        }
+    }
+    
+    /// Swap the two children of the split holding the focused pane
+    pub fn rotate_focused_split(state: &mut AppState) {
+        Self::update_focused_parent_split(state, &mut |split| {
+            if let PanelContent::Split { first, second, .. } = split {
+                std::mem::swap(first, second);
+            }
+        });
+    }
+    
+    /// Switch the split holding the focused pane between horizontal and vertical
+    pub fn flip_focused_split(state: &mut AppState) {
+        Self::update_focused_parent_split(state, &mut |split| {
+            if let PanelContent::Split { direction, .. } = split {
+                *direction = match direction {
+                    SplitDirection::Horizontal => SplitDirection::Vertical,
+                    SplitDirection::Vertical => SplitDirection::Horizontal,
+                };
+            }
+        });
+    }
+    
+    /// Undock a pane and dock it on the `edge` side of `target`, possibly in
+    /// another tab; the backend stays alive. Returns false if nothing moved.
+    pub fn move_pane(state: &mut AppState, source: u64, target: u64, edge: FocusDirection) -> bool {
+        if source == target {
+            return false;
+        }
+        let (Some(source_tab), Some(target_tab)) = (
+            TabManager::find_tab_of_terminal(state, source),
+            TabManager::find_tab_of_terminal(state, target),
+        ) else {
+            return false;
+        };
+        
+        // Moving a tab's only pane would leave an empty tab behind
+        let Some(remaining) = state.tab_layouts.get(&source_tab)
+            .and_then(|layout| Self::remove_terminal_from_layout(layout, source))
+        else {
+            return false;
+        };
+        state.tab_layouts.insert(source_tab, remaining);
+        
+        if let Some(layout) = state.tab_layouts.get_mut(&target_tab) {
+            Self::dock_beside(layout, target, source, edge);
+        }
+        for tab_id in [source_tab, target_tab] {
+            state.zoomed_panes.remove(&tab_id);
+        }
+        state.active_tab_id = target_tab;
+        state.focused_terminal = Some(source);
+        true
+    }
+    
+    /// Side of `rect` nearest to `pos`, used as the dock edge while dragging
+    pub fn drop_edge(rect: Rect, pos: egui::Pos2) -> FocusDirection {
+        let dx = (pos.x - rect.center().x) / rect.width().max(1.0);
+        let dy = (pos.y - rect.center().y) / rect.height().max(1.0);
+        if dx.abs() > dy.abs() {
+            if dx < 0.0 { FocusDirection::Left } else { FocusDirection::Right }
+        } else if dy < 0.0 {
+            FocusDirection::Up
+        } else {
+            FocusDirection::Down
+        }
+    }
+    
+    /// Replace `target` with a split holding `target` and `docked` on its `edge` side
+    fn dock_beside(content: &mut PanelContent, target: u64, docked: u64, edge: FocusDirection) -> bool {
+        match content {
+            PanelContent::Terminal(id) if *id == target => {
+                let direction = match edge {
+                    FocusDirection::Left | FocusDirection::Right => SplitDirection::Vertical,
+                    FocusDirection::Up | FocusDirection::Down => SplitDirection::Horizontal,
+                };
+                let (first, second) = match edge {
+                    FocusDirection::Left | FocusDirection::Up => (docked, target),
+                    FocusDirection::Right | FocusDirection::Down => (target, docked),
+                };
+                *content = PanelContent::Split {
+                    direction,
+                    first: Box::new(PanelContent::Terminal(first)),
+                    second: Box::new(PanelContent::Terminal(second)),
+                    ratio: 0.5,
+                };
+                true
+            }
+            PanelContent::Split { first, second, .. } => {
+                Self::dock_beside(first, target, docked, edge) || Self::dock_beside(second, target, docked, edge)
+            }
+            _ => false,
+        }
+    }
+    
+    fn swap_terminal_ids(content: &mut PanelContent, a: u64, b: u64) {
+        match content {
+            PanelContent::Terminal(id) if *id == a => *id = b,
+            PanelContent::Terminal(id) if *id == b => *id = a,
+            PanelContent::Terminal(_) => {}
+            PanelContent::Split { first, second, .. } => {
+                Self::swap_terminal_ids(first, a, b);
+                Self::swap_terminal_ids(second, a, b);
+            }
+        }
+    }
+    
+    fn update_focused_parent_split(state: &mut AppState, update: &mut dyn FnMut(&mut PanelContent)) {
+        if let Some(terminal_id) = state.focused_terminal {
+            if let Some(layout) = state.tab_layouts.get_mut(&state.active_tab_id) {
+                Self::update_parent_split(layout, terminal_id, update);
+            }
+        }
+    }
+    
+    /// Apply `update` to the split that has the terminal as a direct child
+    fn update_parent_split(content: &mut PanelContent, terminal_id: u64, update: &mut dyn FnMut(&mut PanelContent)) -> bool {
+        let PanelContent::Split { first, second, .. } = content else {
+            return false;
+        };
+        let is_parent = [first.as_ref(), second.as_ref()]
+            .iter()
+            .any(|child| matches!(child, PanelContent::Terminal(id) if *id == terminal_id));
+        if !is_parent {
+            return Self::update_parent_split(first, terminal_id, update)
+                || Self::update_parent_split(second, terminal_id, update);
+        }
+        update(content);
+        true
+    }
+    
+    /// Pane rects for directional lookups; zoomed tabs only render one pane,
+    /// so their hidden panes are laid out as if unzoomed
+    fn navigation_rects(state: &AppState) -> Vec<(u64, Rect)> {
+        let mut rects: Vec<(u64, Rect)> = state.pane_rects.iter()
+            .filter(|(id, _)| !state.zoomed_panes.values().any(|zoomed| zoomed == *id))
+            .map(|(&id, &rect)| (id, rect))
+            .collect();
+        
+        for (tab_id, zoomed) in &state.zoomed_panes {
+            if let (Some(&area), Some(layout)) = (state.pane_rects.get(zoomed), state.tab_layouts.get(tab_id)) {
+                Self::layout_rects(layout, area, &mut rects);
+            }
+        }
+        rects
+    }
+    
+    /// Append the focused terminal to the focus history when it changes
+    pub fn record_focus(state: &mut AppState) {
+        if let Some(focused) = state.focused_terminal {
+            if state.focus_history.last() != Some(&focused) {
+                state.focus_history.retain(|&id| id != focused);
+                state.focus_history.push(focused);
+            }
+        }
+        let terminals = &state.terminals;
+        state.focus_history.retain(|id| terminals.contains_key(id));
+    }
+    
+    /// Pick the pane closest to `current` in `direction`: panes lined up with the
+    /// current one win, then the smallest gap, then the most recently focused
+    fn find_pane_in_direction(
+        rects: &[(u64, Rect)],
+        current: u64,
+        direction: FocusDirection,
+        focus_history: &[u64],
+    ) -> Option<u64> {
+        let current_rect = rects.iter().find(|(id, _)| *id == current)?.1;
+        
+        rects.iter()
+            .filter(|(id, _)| *id != current)
+            .filter_map(|&(id, rect)| {
+                let (gap, offset) = Self::directional_distance(current_rect, rect, direction)?;
+                let recency = focus_history.iter().rposition(|&focused| focused == id);
+                Some((id, offset > 0.0, gap, offset, recency))
+            })
+            .min_by(|a, b| {
+                a.1.cmp(&b.1)
+                    .then(a.2.total_cmp(&b.2))
+                    .then(a.3.total_cmp(&b.3))
+                    .then(b.4.cmp(&a.4))
+            })
+            .map(|(id, ..)| id)
+    }
+    
+    /// Gap between `from` and `to` along `direction` and their offset across it
+    /// (0 when they overlap); None when `to` is not on that side of `from`
+    fn directional_distance(from: Rect, to: Rect, direction: FocusDirection) -> Option<(f32, f32)> {
+        let (gap, from_span, to_span) = match direction {
+            FocusDirection::Left => (from.min.x - to.max.x, from.y_range(), to.y_range()),
+            FocusDirection::Right => (to.min.x - from.max.x, from.y_range(), to.y_range()),
+            FocusDirection::Up => (from.min.y - to.max.y, from.x_range(), to.x_range()),
+            FocusDirection::Down => (to.min.y - from.max.y, from.x_range(), to.x_range()),
+        };
+        if gap < -EDGE_TOLERANCE {
+            return None;
+        }
+        
+        let offset = (from_span.min - to_span.max).max(to_span.min - from_span.max);
+        Some((gap.max(0.0).round(), (offset + EDGE_TOLERANCE).max(0.0).round()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn rect(min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> Rect {
+        Rect::from_min_max(egui::pos2(min_x, min_y), egui::pos2(max_x, max_y))
+    }
+    
+    /// 1 on the left, 2 and 3 stacked on the right, 4 below everything
+    fn layout() -> Vec<(u64, Rect)> {
+        vec![
+            (1, rect(0.0, 0.0, 98.0, 100.0)),
+            (2, rect(102.0, 0.0, 200.0, 48.0)),
+            (3, rect(102.0, 52.0, 200.0, 100.0)),
+            (4, rect(0.0, 104.0, 200.0, 200.0)),
+        ]
+    }
+    
+    fn split(direction: SplitDirection, first: PanelContent, second: PanelContent, ratio: f32) -> PanelContent {
+        PanelContent::Split { direction, first: Box::new(first), second: Box::new(second), ratio }
+    }
+    
+    fn ratio(content: &PanelContent) -> f32 {
+        match content {
+            PanelContent::Split { ratio, .. } => *ratio,
+            PanelContent::Terminal(_) => panic!("not a split"),
+        }
+    }
+    
+    #[test]
+    fn test_resize_uses_nearest_split_of_matching_orientation() {
+        // 1 | (2 over 3)
+        let mut layout = split(
+            SplitDirection::Vertical,
+            PanelContent::Terminal(1),
+            split(SplitDirection::Horizontal, PanelContent::Terminal(2), PanelContent::Terminal(3), 0.5),
+            0.5,
+        );
+        
+        assert_eq!(SplitManager::adjust_enclosing_ratio(&mut layout, 3, SplitDirection::Vertical, -0.05), Some(true));
+        assert_eq!(ratio(&layout), 0.45);
+        
+        assert_eq!(SplitManager::adjust_enclosing_ratio(&mut layout, 3, SplitDirection::Horizontal, 0.05), Some(true));
+        assert_eq!(ratio(&layout), 0.45);
+        if let PanelContent::Split { second, .. } = &layout {
+            assert_eq!(ratio(second), 0.55);
+        }
+        
+        // No horizontal split above pane 1
+        assert_eq!(SplitManager::adjust_enclosing_ratio(&mut layout, 1, SplitDirection::Horizontal, 0.05), Some(false));
+        assert_eq!(SplitManager::adjust_enclosing_ratio(&mut layout, 9, SplitDirection::Vertical, 0.05), None);
+    }
+    
+    #[test]
+    fn test_equalize_gives_leaves_equal_area() {
+        // 1 | (2 over (3 | 4))
+        let mut layout = split(
+            SplitDirection::Vertical,
+            PanelContent::Terminal(1),
+            split(
+                SplitDirection::Horizontal,
+                PanelContent::Terminal(2),
+                split(SplitDirection::Vertical, PanelContent::Terminal(3), PanelContent::Terminal(4), 0.8),
+                0.2,
+            ),
+            0.7,
+        );
+        SplitManager::equalize_layout(&mut layout);
+        
+        assert_eq!(ratio(&layout), 0.25);
+        if let PanelContent::Split { second, .. } = &layout {
+            assert!((ratio(second) - 1.0 / 3.0).abs() < 1e-6);
+            if let PanelContent::Split { second, .. } = second.as_ref() {
+                assert_eq!(ratio(second), 0.5);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_layout_rects_follow_split_geometry() {
+        let layout = split(SplitDirection::Vertical, PanelContent::Terminal(1), PanelContent::Terminal(2), 0.25);
+        let mut rects = Vec::new();
+        SplitManager::layout_rects(&layout, rect(0.0, 0.0, 200.0, 100.0), &mut rects);
+        
+        assert_eq!(rects, vec![(1, rect(0.0, 0.0, 48.0, 100.0)), (2, rect(52.0, 0.0, 200.0, 100.0))]);
+        assert_eq!(SplitManager::find_pane_in_direction(&rects, 1, FocusDirection::Right, &[]), Some(2));
+    }
+    
+    #[test]
+    fn test_rearranging_the_tree() {
+        // 1 | 2
+        let mut layout = split(SplitDirection::Vertical, PanelContent::Terminal(1), PanelContent::Terminal(2), 0.3);
+        
+        SplitManager::swap_terminal_ids(&mut layout, 1, 2);
+        assert_eq!(TabManager::collect_terminal_ids(&layout), vec![2, 1]);
+        
+        assert!(SplitManager::update_parent_split(&mut layout, 1, &mut |split| {
+            if let PanelContent::Split { first, second, .. } = split {
+                std::mem::swap(first, second);
+            }
+        }));
+        assert_eq!(TabManager::collect_terminal_ids(&layout), vec![1, 2]);
+        assert_eq!(ratio(&layout), 0.3);
+        
+        // Dock 3 above 2: 1 | (3 over 2)
+        assert!(SplitManager::dock_beside(&mut layout, 2, 3, FocusDirection::Up));
+        assert_eq!(TabManager::collect_terminal_ids(&layout), vec![1, 3, 2]);
+        match &layout {
+            PanelContent::Split { second, .. } => {
+                assert!(matches!(second.as_ref(), PanelContent::Split { direction: SplitDirection::Horizontal, .. }));
+            }
+            PanelContent::Terminal(_) => panic!("layout collapsed"),
+        }
+    }
+    
+    #[test]
+    fn test_joined_layout_is_grafted_beside_target() {
+        let mut layout = split(SplitDirection::Horizontal, PanelContent::Terminal(1), PanelContent::Terminal(2), 0.5);
+        let joined = split(SplitDirection::Vertical, PanelContent::Terminal(5), PanelContent::Terminal(6), 0.4);
+        
+        assert!(SplitManager::replace_terminal_with_split_static(&mut layout, 2, SplitDirection::Vertical, 5));
+        assert!(SplitManager::replace_terminal(&mut layout, 5, joined));
+        assert_eq!(TabManager::collect_terminal_ids(&layout), vec![1, 2, 5, 6]);
+        
+        // Breaking a pane back out leaves the rest of the tree in place
+        let remaining = SplitManager::remove_terminal_from_layout(&layout, 5).unwrap();
+        assert_eq!(TabManager::collect_terminal_ids(&remaining), vec![1, 2, 6]);
+        assert!(SplitManager::remove_terminal_from_layout(&PanelContent::Terminal(6), 6).is_none());
+    }
+    
+    #[test]
+    fn test_drop_edge() {
+        let area = rect(0.0, 0.0, 200.0, 100.0);
+        assert_eq!(SplitManager::drop_edge(area, egui::pos2(10.0, 50.0)), FocusDirection::Left);
+        assert_eq!(SplitManager::drop_edge(area, egui::pos2(190.0, 40.0)), FocusDirection::Right);
+        assert_eq!(SplitManager::drop_edge(area, egui::pos2(100.0, 5.0)), FocusDirection::Up);
+        assert_eq!(SplitManager::drop_edge(area, egui::pos2(90.0, 95.0)), FocusDirection::Down);
+    }
+    
+    #[test]
+    fn test_moves_in_pressed_direction() {
+        let rects = layout();
+        assert_eq!(SplitManager::find_pane_in_direction(&rects, 2, FocusDirection::Left, &[]), Some(1));
+        assert_eq!(SplitManager::find_pane_in_direction(&rects, 2, FocusDirection::Down, &[]), Some(3));
+        assert_eq!(SplitManager::find_pane_in_direction(&rects, 3, FocusDirection::Up, &[]), Some(2));
+        assert_eq!(SplitManager::find_pane_in_direction(&rects, 1, FocusDirection::Down, &[]), Some(4));
+        assert_eq!(SplitManager::find_pane_in_direction(&rects, 1, FocusDirection::Left, &[]), None);
+        assert_eq!(SplitManager::find_pane_in_direction(&rects, 2, FocusDirection::Right, &[]), None);
+    }
+    
+    #[test]
+    fn test_most_recently_focused_breaks_ties() {
+        let rects = layout();
+        assert_eq!(SplitManager::find_pane_in_direction(&rects, 1, FocusDirection::Right, &[3, 2]), Some(2));
+        assert_eq!(SplitManager::find_pane_in_direction(&rects, 1, FocusDirection::Right, &[2, 3]), Some(3));
+        // 2 is more recent but farther away than 1 and 3
+        assert_eq!(SplitManager::find_pane_in_direction(&rects, 4, FocusDirection::Up, &[1, 3, 2]), Some(3));
+        assert_eq!(SplitManager::find_pane_in_direction(&rects, 4, FocusDirection::Up, &[3, 1, 2]), Some(1));
+    }
 }
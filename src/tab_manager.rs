@@ -1,26 +1,75 @@
-use crate::types::{AppState, TerminalTab, PanelContent};
+use crate::types::{AppState, TerminalTab, PanelContent, TabSwitcher, SwitcherEntry};
+use crate::split_manager::SplitManager;
+
+/// Accent colors offered for tabs
+pub const TAB_COLORS: &[(&str, egui::Color32)] = &[
+    ("Red", egui::Color32::from_rgb(220, 80, 80)),
+    ("Orange", egui::Color32::from_rgb(230, 150, 60)),
+    ("Yellow", egui::Color32::from_rgb(220, 200, 70)),
+    ("Green", egui::Color32::from_rgb(90, 180, 90)),
+    ("Blue", egui::Color32::from_rgb(70, 140, 230)),
+    ("Purple", egui::Color32::from_rgb(160, 100, 210)),
+];
 
 pub struct TabManager;
 
 impl TabManager {
     /// Create a new terminal tab
     pub fn create_new_tab(state: &mut AppState) {
-        let tab_id = state.next_tab_id;
-        state.next_tab_id += 1;
-        
         // Create a terminal for this tab
         let terminal_id = state.create_terminal();
+        Self::insert_tab(state, terminal_id, state.tab_order.len());
+    }
+    
+    /// Open a tab next to `tab_id` in the working directory of its last used pane,
+    /// carrying over the tab's color
+    pub fn duplicate_tab(state: &mut AppState, tab_id: u64) {
+        let Some(index) = state.tab_order.iter().position(|&id| id == tab_id) else {
+            return;
+        };
+        let working_directory = Self::last_used_terminal(state, tab_id)
+            .and_then(|terminal_id| state.terminals.get(&terminal_id))
+            .and_then(|terminal| terminal.working_directory());
+        let color = state.tabs.get(&tab_id).and_then(|tab| tab.color);
         
-        let tab = TerminalTab {
-            id: tab_id,
-            title: format!("Terminal {}", tab_id),
+        let terminal_id = state.create_terminal_with_cwd(working_directory);
+        Self::insert_tab(state, terminal_id, index + 1);
+        Self::set_color(state, state.active_tab_id, color);
+    }
+    
+    /// Move a pane out of its split into a new tab right after its old one;
+    /// the terminal keeps running. Returns false for a tab's only pane.
+    pub fn break_pane_to_tab(state: &mut AppState, terminal_id: u64) -> bool {
+        let Some(source_tab) = Self::find_tab_of_terminal(state, terminal_id) else {
+            return false;
+        };
+        let Some(remaining) = state.tab_layouts.get(&source_tab)
+            .and_then(|layout| SplitManager::remove_terminal_from_layout(layout, terminal_id))
+        else {
+            return false;
         };
+        state.tab_layouts.insert(source_tab, remaining);
+        state.zoomed_panes.remove(&source_tab);
+        
+        let index = state.tab_order.iter()
+            .position(|&id| id == source_tab)
+            .map_or(state.tab_order.len(), |position| position + 1);
+        Self::insert_tab(state, terminal_id, index);
+        true
+    }
+    
+    /// Add a tab holding a single existing terminal at `index` in the tab order
+    fn insert_tab(state: &mut AppState, terminal_id: u64, index: usize) {
+        let tab_id = state.next_tab_id;
+        state.next_tab_id += 1;
+        
+        let tab = TerminalTab::new(tab_id);
         
         // Set up the layout with a single terminal
         let layout = PanelContent::Terminal(terminal_id);
         
         state.tabs.insert(tab_id, tab);
-        state.tab_order.push(tab_id); // Maintain order
+        state.tab_order.insert(index.min(state.tab_order.len()), tab_id); // Maintain order
         state.tab_layouts.insert(tab_id, layout);
         state.active_tab_id = tab_id;
         state.focused_terminal = Some(terminal_id);
@@ -38,11 +87,12 @@ impl TabManager {
             // Remove associated terminals
             if let Some(layout) = state.tab_layouts.remove(&tab_id) {
                 Self::collect_terminal_ids(&layout).into_iter().for_each(|tid| {
-                    state.terminals.remove(&tid);
+                    SplitManager::forget_terminal(state, tid);
                 });
             }
             
             state.tabs.remove(&tab_id);
+            state.zoomed_panes.remove(&tab_id);
             state.tab_order.retain(|&id| id != tab_id); // Remove from order
             
             // Update grid size after tab removal
@@ -84,13 +134,87 @@ impl TabManager {
     pub fn switch_to_tab(state: &mut AppState, tab_id: u64) {
         if state.tabs.contains_key(&tab_id) {
             state.active_tab_id = tab_id;
-            // Update focus to the first terminal in this tab
-            if let Some(layout) = state.tab_layouts.get(&tab_id) {
-                state.focused_terminal = Self::get_first_terminal_id(layout);
+            // Focus the pane last used in this tab, or its first terminal
+            state.focused_terminal = Self::last_used_terminal(state, tab_id);
+        }
+    }
+    
+    /// Step through the tab bar order, wrapping at either end; with
+    /// `tabs.mru_switcher` this opens or advances the MRU switcher instead
+    pub fn switch_relative(state: &mut AppState, offset: isize) {
+        if state.config.tabs.mru_switcher {
+            Self::cycle_switcher(state, offset);
+            return;
+        }
+        
+        let count = state.tab_order.len();
+        if let Some(index) = state.tab_order.iter().position(|&id| id == state.active_tab_id) {
+            let target = (index as isize + offset).rem_euclid(count as isize) as usize;
+            Self::switch_to_tab(state, state.tab_order[target]);
+        }
+    }
+    
+    /// Open the switcher on the previously used tab, or move its selection
+    fn cycle_switcher(state: &mut AppState, offset: isize) {
+        if state.tab_switcher.is_none() {
+            if state.tabs.len() < 2 {
+                return;
+            }
+            let entries = Self::tabs_by_recent_use(state).into_iter()
+                .filter_map(|tab_id| {
+                    let tab = state.tabs.get(&tab_id)?;
+                    let working_directory = Self::last_used_terminal(state, tab_id)
+                        .and_then(|terminal_id| state.terminals.get(&terminal_id))
+                        .and_then(|terminal| terminal.working_directory());
+                    Some(SwitcherEntry { tab_id, title: Self::tab_title(state, tab_id, tab), working_directory })
+                })
+                .collect();
+            let held = state.egui_ctx.input(|i| egui::Modifiers { shift: false, ..i.modifiers });
+            state.tab_switcher = Some(TabSwitcher { entries, selected: 0, held });
+        }
+        
+        if let Some(switcher) = state.tab_switcher.as_mut() {
+            let count = switcher.entries.len() as isize;
+            switcher.selected = (switcher.selected as isize + offset).rem_euclid(count) as usize;
+            
+            // Without a held modifier (leader, palette) there is nothing to release
+            if switcher.held.is_none() {
+                Self::finish_switcher(state, true);
+            }
+        }
+    }
+    
+    /// Close the switcher, activating its selection when `commit` is set
+    pub fn finish_switcher(state: &mut AppState, commit: bool) {
+        if let Some(switcher) = state.tab_switcher.take() {
+            if let Some(entry) = switcher.entries.get(switcher.selected).filter(|_| commit) {
+                Self::switch_to_tab(state, entry.tab_id);
             }
         }
     }
     
+    /// Tab ids with the active tab first, then by most recent use, then the rest in tab order
+    fn tabs_by_recent_use(state: &AppState) -> Vec<u64> {
+        let mut tab_ids = vec![state.active_tab_id];
+        tab_ids.extend(state.tab_history.iter().rev());
+        tab_ids.extend(&state.tab_order);
+        
+        let mut seen = std::collections::HashSet::new();
+        tab_ids.retain(|tab_id| state.tabs.contains_key(tab_id) && seen.insert(*tab_id));
+        tab_ids
+    }
+    
+    /// Append the active tab to the tab history when it changes
+    pub fn record_active_tab(state: &mut AppState) {
+        let active = state.active_tab_id;
+        if state.tab_history.last() != Some(&active) {
+            state.tab_history.retain(|&id| id != active);
+            state.tab_history.push(active);
+        }
+        let tabs = &state.tabs;
+        state.tab_history.retain(|id| tabs.contains_key(id));
+    }
+    
     /// Switch to tab by index (0-based)
     pub fn switch_to_tab_by_index(state: &mut AppState, index: usize) {
         if index < state.tab_order.len() {
@@ -99,6 +223,106 @@ impl TabManager {
         }
     }
     
+    /// Move a tab by `offset` places in the tab order, stopping at either end
+    pub fn move_tab_by(state: &mut AppState, tab_id: u64, offset: isize) {
+        if let Some(index) = state.tab_order.iter().position(|&id| id == tab_id) {
+            let target = index.saturating_add_signed(offset).min(state.tab_order.len() - 1);
+            let tab_id = state.tab_order.remove(index);
+            state.tab_order.insert(target, tab_id);
+        }
+    }
+    
+    /// Move a tab into the gap before `gap` (0 ..= tab count), as counted
+    /// while the tab is still in its old place
+    pub fn move_tab_to_gap(state: &mut AppState, tab_id: u64, gap: usize) {
+        Self::reorder(&mut state.tab_order, tab_id, gap);
+    }
+    
+    fn reorder(order: &mut Vec<u64>, tab_id: u64, gap: usize) {
+        if let Some(index) = order.iter().position(|&id| id == tab_id) {
+            order.remove(index);
+            let target = if gap > index { gap - 1 } else { gap };
+            order.insert(target.min(order.len()), tab_id);
+        }
+    }
+    
+    /// Title shown in the tab bar and grid headers, marked when a pane is zoomed
+    pub fn display_title(state: &AppState, tab_id: u64, tab: &TerminalTab) -> String {
+        let title = Self::tab_title(state, tab_id, tab);
+        if state.zoomed_panes.contains_key(&tab_id) {
+            format!("[Z] {}", title)
+        } else {
+            title
+        }
+    }
+    
+    /// The pinned title, else the OSC title of the tab's most recently
+    /// focused pane, else the default name
+    pub fn tab_title(state: &AppState, tab_id: u64, tab: &TerminalTab) -> String {
+        if let Some(pinned) = &tab.pinned_title {
+            return pinned.clone();
+        }
+        
+        Self::last_used_terminal(state, tab_id)
+            .and_then(|terminal_id| state.terminal_titles.get(&terminal_id))
+            .filter(|title| !title.is_empty())
+            .cloned()
+            .unwrap_or_else(|| tab.title.clone())
+    }
+    
+    /// The tab's focused pane, else its most recently focused one, else its first
+    pub fn last_used_terminal(state: &AppState, tab_id: u64) -> Option<u64> {
+        let terminal_ids = Self::collect_terminal_ids(state.tab_layouts.get(&tab_id)?);
+        state.focused_terminal.iter()
+            .chain(state.focus_history.iter().rev())
+            .find(|id| terminal_ids.contains(id))
+            .or(terminal_ids.first())
+            .copied()
+    }
+    
+    /// Open the inline title editor for a tab
+    pub fn start_rename(state: &mut AppState, tab_id: u64) {
+        if let Some(tab) = state.tabs.get(&tab_id) {
+            let title = Self::tab_title(state, tab_id, tab);
+            state.renaming_tab = Some((tab_id, title));
+        }
+    }
+    
+    /// Close the title editor; a committed name is pinned, an empty one
+    /// returns the tab to automatic titles
+    pub fn finish_rename(state: &mut AppState, commit: bool) {
+        if let Some((tab_id, text)) = state.renaming_tab.take() {
+            if commit {
+                let text = text.trim();
+                Self::set_pinned_title(state, tab_id, (!text.is_empty()).then(|| text.to_string()));
+            }
+        }
+    }
+    
+    pub fn set_pinned_title(state: &mut AppState, tab_id: u64, title: Option<String>) {
+        if let Some(tab) = state.tabs.get_mut(&tab_id) {
+            tab.pinned_title = title;
+        }
+    }
+    
+    pub fn set_color(state: &mut AppState, tab_id: u64, color: Option<egui::Color32>) {
+        if let Some(tab) = state.tabs.get_mut(&tab_id) {
+            tab.color = color;
+        }
+    }
+    
+    /// Widget id of a tab's title editor
+    pub fn rename_id(tab_id: u64) -> egui::Id {
+        egui::Id::new(("tab_rename", tab_id))
+    }
+    
+    /// Find the tab whose layout contains a terminal
+    pub fn find_tab_of_terminal(state: &AppState, terminal_id: u64) -> Option<u64> {
+        state.tab_layouts.iter()
+            .find(|(_, layout)| SplitManager::contains_terminal(layout, terminal_id))
+            .map(|(&tab_id, _)| tab_id)
+    }
+    
     /// Get all terminal IDs in a layout
     pub fn collect_terminal_ids(content: &PanelContent) -> Vec<u64> {
         match content {
@@ -119,3 +343,27 @@ impl TabManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_reorder_into_gap() {
+        let mut order = vec![1, 2, 3, 4];
+        
+        // Dropping a tab next to itself keeps the order
+        TabManager::reorder(&mut order, 2, 1);
+        TabManager::reorder(&mut order, 2, 2);
+        assert_eq!(order, vec![1, 2, 3, 4]);
+        
+        TabManager::reorder(&mut order, 1, 3);
+        assert_eq!(order, vec![2, 3, 1, 4]);
+        
+        TabManager::reorder(&mut order, 4, 0);
+        assert_eq!(order, vec![4, 2, 3, 1]);
+        
+        TabManager::reorder(&mut order, 4, 4);
+        assert_eq!(order, vec![2, 3, 1, 4]);
+    }
+}
@@ -0,0 +1,323 @@
+use crate::config_manager::set_palette_color;
+use egui_term::ColorPalette;
+use std::path::Path;
+
+/// Normal colors that get a dim variant when a scheme does not define one
+const DIMMABLE: [&str; 9] = ["foreground", "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+/// Brightness of derived dim colors, as alacritty does it
+const DIM_FACTOR: f32 = 0.66;
+
+/// Palette names of the 16 ANSI colors, by index
+const ANSI_NAMES: [&str; 16] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    "bright_black", "bright_red", "bright_green", "bright_yellow",
+    "bright_blue", "bright_magenta", "bright_cyan", "bright_white",
+];
+
+/// Read a color scheme file, picking the format from its extension:
+/// `.toml` and `.yml`/`.yaml` alacritty configs, base16 `.yaml` schemes and
+/// iTerm2 `.itermcolors` plists
+pub fn import_theme_file(path: &Path) -> Result<ColorPalette, String> {
+    let data = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let text = || std::str::from_utf8(&data).map_err(|e| e.to_string());
+    let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or_default();
+    
+    let colors = match extension.to_ascii_lowercase().as_str() {
+        "toml" => text().and_then(alacritty_toml_colors),
+        "yml" | "yaml" => text().and_then(yaml_colors),
+        "itermcolors" | "plist" => iterm_colors(&data),
+        _ => Err(format!("unknown color scheme format `.{}`", extension)),
+    };
+    colors.and_then(|colors| palette_from_colors(&colors))
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Build a palette from `(palette name, color)` pairs on top of the default
+/// palette, deriving dim colors the scheme leaves out
+pub fn palette_from_colors(colors: &[(String, String)]) -> Result<ColorPalette, String> {
+    if colors.is_empty() {
+        return Err(String::from("no colors found"));
+    }
+    
+    let mut palette = ColorPalette::default();
+    for (name, value) in colors {
+        set_palette_color(&mut palette, name, value)?;
+    }
+    for name in DIMMABLE {
+        let dim_name = format!("dim_{}", name);
+        if !colors.iter().any(|(set, _)| *set == dim_name) {
+            if let Some((_, value)) = colors.iter().find(|(set, _)| set == name) {
+                set_palette_color(&mut palette, &dim_name, &dim_color(value))?;
+            }
+        }
+    }
+    Ok(palette)
+}
+
+/// Colors of an alacritty TOML config (`[colors.primary]`, `[colors.normal]`, ...)
+pub fn alacritty_toml_colors(text: &str) -> Result<Vec<(String, String)>, String> {
+    let table: toml::Table = toml::from_str(text).map_err(|e| e.message().to_string())?;
+    let mut entries = Vec::new();
+    flatten_toml("", &toml::Value::Table(table), &mut entries);
+    alacritty_colors(&entries)
+}
+
+/// Colors of an alacritty YAML config or a base16 scheme
+pub fn yaml_colors(text: &str) -> Result<Vec<(String, String)>, String> {
+    let mut value: serde_yaml::Value = serde_yaml::from_str(text).map_err(|e| e.to_string())?;
+    value.apply_merge().map_err(|e| e.to_string())?;
+    let mut entries = Vec::new();
+    flatten_yaml("", &value, &mut entries);
+    if entries.iter().any(|(key, _)| key == "base00") {
+        base16_colors(&entries)
+    } else {
+        alacritty_colors(&entries)
+    }
+}
+
+fn flatten_toml(prefix: &str, value: &toml::Value, entries: &mut Vec<(String, String)>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten_toml(&path, value, entries);
+            }
+        }
+        toml::Value::String(text) => entries.push((prefix.to_string(), text.clone())),
+        _ => {}
+    }
+}
+
+fn flatten_yaml(prefix: &str, value: &serde_yaml::Value, entries: &mut Vec<(String, String)>) {
+    match value {
+        serde_yaml::Value::Mapping(mapping) => {
+            for (key, value) in mapping {
+                let Some(key) = key.as_str() else {
+                    continue;
+                };
+                let path = if prefix.is_empty() { key.to_string() } else { format!("{}.{}", prefix, key) };
+                flatten_yaml(&path, value, entries);
+            }
+        }
+        serde_yaml::Value::String(text) => entries.push((prefix.to_string(), text.clone())),
+        serde_yaml::Value::Tagged(tagged) => flatten_yaml(prefix, &tagged.value, entries),
+        _ => {}
+    }
+}
+
+/// Map alacritty's `colors.<group>.<color>` keys to palette names
+fn alacritty_colors(entries: &[(String, String)]) -> Result<Vec<(String, String)>, String> {
+    let mut colors = Vec::new();
+    for (key, value) in entries {
+        let Some((group, color)) = key.strip_prefix("colors.").and_then(|rest| rest.split_once('.')) else {
+            continue;
+        };
+        let name = match group {
+            "primary" if matches!(color, "foreground" | "background" | "dim_foreground" | "bright_foreground") => {
+                color.to_string()
+            }
+            "normal" if ANSI_NAMES[..8].contains(&color) => color.to_string(),
+            "bright" | "dim" if ANSI_NAMES[..8].contains(&color) => format!("{}_{}", group, color),
+            _ => continue,
+        };
+        colors.push((name, hex_color(value).ok_or_else(|| format!("{}: `{}` is not a color", key, value))?));
+    }
+    Ok(colors)
+}
+
+/// Map a base16 scheme to the 16 colors the way base16-shell does
+fn base16_colors(entries: &[(String, String)]) -> Result<Vec<(String, String)>, String> {
+    const SLOTS: [(&str, &str); 18] = [
+        ("background", "base00"), ("foreground", "base05"),
+        ("black", "base00"), ("red", "base08"), ("green", "base0B"), ("yellow", "base0A"),
+        ("blue", "base0D"), ("magenta", "base0E"), ("cyan", "base0C"), ("white", "base05"),
+        ("bright_black", "base03"), ("bright_red", "base08"), ("bright_green", "base0B"),
+        ("bright_yellow", "base0A"), ("bright_blue", "base0D"), ("bright_magenta", "base0E"),
+        ("bright_cyan", "base0C"), ("bright_white", "base07"),
+    ];
+    
+    SLOTS.iter()
+        .map(|&(name, base)| {
+            let value = entries.iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(base))
+                .map(|(_, value)| value)
+                .ok_or_else(|| format!("missing {}", base))?;
+            let color = hex_color(value).ok_or_else(|| format!("{}: `{}` is not a color", base, value))?;
+            Ok((name.to_string(), color))
+        })
+        .collect()
+}
+
+/// Colors of an iTerm2 `.itermcolors` plist, XML or binary
+pub fn iterm_colors(data: &[u8]) -> Result<Vec<(String, String)>, String> {
+    let plist = plist::Value::from_reader(std::io::Cursor::new(data)).map_err(|e| e.to_string())?;
+    let dict = plist.as_dictionary().ok_or("expected a dictionary")?;
+    
+    let mut colors = Vec::new();
+    for (key, value) in dict {
+        let (Some(name), Some(color)) = (iterm_color_name(key), value.as_dictionary()) else {
+            continue;
+        };
+        let mut components = [0.0f32; 3];
+        for (index, component) in ["Red Component", "Green Component", "Blue Component"].into_iter().enumerate() {
+            let Some(value) = color.get(component) else {
+                continue;
+            };
+            let number = value.as_real()
+                .or_else(|| value.as_signed_integer().map(|integer| integer as f64))
+                .ok_or_else(|| format!("{}: {} is not a number", key, component))?;
+            components[index] = number as f32;
+        }
+        colors.push((name.to_string(), rgb_hex(components)));
+    }
+    Ok(colors)
+}
+
+fn iterm_color_name(key: &str) -> Option<&'static str> {
+    match key {
+        "Foreground Color" => Some("foreground"),
+        "Background Color" => Some("background"),
+        "Bold Color" => Some("bright_foreground"),
+        _ => {
+            let index: usize = key.strip_prefix("Ansi ")?.strip_suffix(" Color")?.parse().ok()?;
+            ANSI_NAMES.get(index).copied()
+        }
+    }
+}
+
+/// `#rrggbb`, `0xrrggbb` or bare `rrggbb` as lowercase `#rrggbb`
+fn hex_color(value: &str) -> Option<String> {
+    let digits = value.strip_prefix('#')
+        .or_else(|| value.strip_prefix("0x"))
+        .unwrap_or(value);
+    (digits.len() == 6 && digits.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| format!("#{}", digits.to_ascii_lowercase()))
+}
+
+fn rgb_hex(components: [f32; 3]) -> String {
+    let [r, g, b] = components.map(|component| (component.clamp(0.0, 1.0) * 255.0).round() as u8);
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// `#rrggbb` darkened by `DIM_FACTOR`
+fn dim_color(value: &str) -> String {
+    let channel = |index: usize| {
+        let component = u8::from_str_radix(&value[index..index + 2], 16).unwrap_or(0);
+        (component as f32 * DIM_FACTOR) as u8
+    };
+    format!("#{:02x}{:02x}{:02x}", channel(1), channel(3), channel(5))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn color<'a>(colors: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        colors.iter().find(|(set, _)| set == name).map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn test_alacritty_toml_and_yaml() {
+        let toml = "[colors.primary]\nbackground = \"#1D1F21\"\n[colors.bright]\nred = \"0xff5555\"\n";
+        let colors = alacritty_toml_colors(toml).unwrap();
+        assert_eq!(color(&colors, "background"), Some("#1d1f21"));
+        assert_eq!(color(&colors, "bright_red"), Some("#ff5555"));
+
+        let yaml = "# Colors\ncolors:\n  primary:\n    background: '#1d1f21' # dark\n  normal:\n    red:   \"0xcc6666\"\nfont:\n  size: 11\n";
+        let colors = yaml_colors(yaml).unwrap();
+        assert_eq!(colors, vec![
+            ("background".to_string(), "#1d1f21".to_string()),
+            ("red".to_string(), "#cc6666".to_string()),
+        ]);
+        assert!(alacritty_toml_colors("[colors.normal]\nred = \"crimson\"").is_err());
+    }
+
+    #[test]
+    fn test_base16_scheme() {
+        let mut yaml = String::from("scheme: \"Test\"\nauthor: \"someone\"\n");
+        for index in 0..16 {
+            yaml.push_str(&format!("base0{:X}: \"{:02x}{:02x}{:02x}\"\n", index, index, index, index));
+        }
+        let colors = yaml_colors(&yaml).unwrap();
+        assert_eq!(color(&colors, "background"), Some("#000000"));
+        assert_eq!(color(&colors, "red"), Some("#080808"));
+        assert_eq!(color(&colors, "bright_white"), Some("#070707"));
+        assert!(yaml_colors("base00: \"000000\"\n").is_err());
+    }
+
+    #[test]
+    fn test_iterm_plist() {
+        let plist = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+	<key>Ansi 1 Color</key>
+	<dict>
+		<key>Color Space</key>
+		<string>sRGB</string>
+		<key>Blue Component</key>
+		<real>0.0</real>
+		<key>Green Component</key>
+		<real>0.5</real>
+		<key>Red Component</key>
+		<real>1</real>
+	</dict>
+	<key>Selection Color</key>
+	<dict>
+		<key>Red Component</key>
+		<real>0.2</real>
+	</dict>
+</dict>
+</plist>"#;
+        assert_eq!(iterm_colors(plist.as_bytes()).unwrap(), vec![("red".to_string(), "#ff8000".to_string())]);
+    }
+
+    #[test]
+    fn test_real_scheme_files() {
+        let colors = iterm_colors(include_bytes!("../testdata/Dracula.itermcolors")).unwrap();
+        assert_eq!(colors.len(), 19);
+        assert_eq!(color(&colors, "background"), Some("#282a36"));
+        assert_eq!(color(&colors, "bright_foreground"), Some("#ffffff"));
+        assert_eq!(color(&colors, "blue"), Some("#bd93f9"));
+        assert_eq!(color(&colors, "bright_cyan"), Some("#a4ffff"));
+
+        // `colors: *dracula` picks one of the anchored schemes
+        let colors = yaml_colors(include_str!("../testdata/alacritty.yml")).unwrap();
+        assert_eq!(colors.len(), 19);
+        assert_eq!(color(&colors, "background"), Some("#282a36"));
+        assert_eq!(color(&colors, "bright_foreground"), Some("#ffffff"));
+        assert_eq!(color(&colors, "red"), Some("#ff5555"));
+        assert_eq!(color(&colors, "bright_white"), Some("#ffffff"));
+    }
+
+    #[test]
+    fn test_yaml_and_plist_syntax() {
+        let colors = yaml_colors("colors:\n  primary: { background: '#000000' }\n").unwrap();
+        assert_eq!(color(&colors, "background"), Some("#000000"));
+        let colors = yaml_colors("dark: &dark\n  primary:\n    background: '#111111'\ncolors:\n  <<: *dark\n").unwrap();
+        assert_eq!(color(&colors, "background"), Some("#111111"));
+        assert!(yaml_colors("colors: *missing\n").is_err());
+        assert!(yaml_colors("colors:\n  normal:\n    red: >-\n      crimson\n").is_err());
+
+        let dict = |body: &str| format!("<plist><dict><key>Ansi 1 Color</key><dict>{}</dict></dict></plist>", body);
+        assert_eq!(
+            iterm_colors(dict("<key>Name &amp; &#x263A; note</key><string>&lt;a&gt;</string><key>Red Component</key><real>1</real>").as_bytes()).unwrap(),
+            vec![("red".to_string(), "#ff0000".to_string())]
+        );
+        assert_eq!(
+            iterm_colors(dict("<key>Red Component</key><integer>1</integer>").as_bytes()).unwrap(),
+            vec![("red".to_string(), "#ff0000".to_string())]
+        );
+        assert!(iterm_colors(dict("<key>Red Component</key><real>&unknown;</real>").as_bytes()).is_err());
+        assert!(iterm_colors(dict("<key>Red Component</key><array/>").as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_palette_derives_dim_colors() {
+        let colors = vec![("red".to_string(), "#ac4242".to_string())];
+        let palette = palette_from_colors(&colors).unwrap();
+        assert_eq!(palette.red, "#ac4242");
+        assert_eq!(palette.dim_red, "#712b2b");
+        assert!(palette_from_colors(&[]).is_err());
+    }
+}
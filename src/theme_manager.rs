@@ -0,0 +1,117 @@
+use crate::types::AppState;
+use crate::config_manager::{apply_theme_colors, expand_home, ThemeConfig};
+use crate::theme_import::{alacritty_toml_colors, import_theme_file, palette_from_colors};
+use egui_term::{ColorPalette, TerminalTheme};
+use std::path::Path;
+
+/// Name of the built-in palette
+pub const DEFAULT_THEME: &str = "Default";
+
+/// Color schemes compiled into the binary, in alacritty's TOML format
+const BUNDLED_THEMES: &[(&str, &str)] = &[
+    ("Dracula", include_str!("../themes/dracula.toml")),
+    ("Gruvbox Dark", include_str!("../themes/gruvbox_dark.toml")),
+    ("Nord", include_str!("../themes/nord.toml")),
+    ("One Dark", include_str!("../themes/one_dark.toml")),
+    ("Solarized Dark", include_str!("../themes/solarized_dark.toml")),
+    ("Solarized Light", include_str!("../themes/solarized_light.toml")),
+    ("Tokyo Night", include_str!("../themes/tokyo_night.toml")),
+];
+
+/// Theme picker window; picking a theme applies it right away
+#[derive(Debug, Clone, Default)]
+pub struct ThemePicker {
+    pub import_path: String,
+    pub error: Option<String>, // Set when importing a file failed
+}
+
+pub struct ThemeManager;
+
+impl ThemeManager {
+    /// Names offered by the picker, the built-in palette first
+    pub fn theme_names() -> impl Iterator<Item = &'static str> {
+        std::iter::once(DEFAULT_THEME).chain(BUNDLED_THEMES.iter().map(|(name, _)| *name))
+    }
+    
+    /// Palette of a bundled theme, matched case-insensitively
+    pub fn bundled_palette(name: &str) -> Option<ColorPalette> {
+        if name.eq_ignore_ascii_case(DEFAULT_THEME) {
+            return Some(ColorPalette::default());
+        }
+        let (_, scheme) = BUNDLED_THEMES.iter().find(|(bundled, _)| bundled.eq_ignore_ascii_case(name))?;
+        let colors = alacritty_toml_colors(scheme).expect("bundled themes are valid");
+        Some(palette_from_colors(&colors).expect("bundled themes are valid"))
+    }
+    
+    /// Theme named by `theme.file` or `theme.name` in the config, before its color overrides
+    pub fn configured_palette(theme: &ThemeConfig) -> Result<(String, ColorPalette), String> {
+        match (&theme.file, &theme.name) {
+            (Some(_), Some(_)) => Err(String::from("theme: set either `name` or `file`, not both")),
+            (Some(file), None) => {
+                let palette = import_theme_file(&expand_home(file)).map_err(|e| format!("theme.file: {}", e))?;
+                Ok((Self::file_theme_name(file), palette))
+            }
+            (None, Some(name)) => Self::bundled_palette(name)
+                .map(|palette| (name.clone(), palette))
+                .ok_or_else(|| format!("theme.name: unknown theme `{}`", name)),
+            (None, None) => Ok((DEFAULT_THEME.to_string(), ColorPalette::default())),
+        }
+    }
+    
+    /// Open the theme picker
+    pub fn open_picker(state: &mut AppState) {
+        state.theme_picker = Some(ThemePicker::default());
+    }
+    
+    /// Switch every pane to a bundled theme
+    pub fn select(state: &mut AppState, name: &str) {
+        if let Some(palette) = Self::bundled_palette(name) {
+            Self::apply(state, name.to_string(), palette);
+        }
+    }
+    
+    /// Switch every pane to the scheme in the picker's file; errors stay in the picker
+    pub fn import(state: &mut AppState) {
+        let Some(picker) = &mut state.theme_picker else {
+            return;
+        };
+        let path = expand_home(Path::new(picker.import_path.trim()));
+        match import_theme_file(&path) {
+            Ok(palette) => {
+                picker.error = None;
+                Self::apply(state, Self::file_theme_name(&path), palette);
+            }
+            Err(error) => picker.error = Some(error),
+        }
+    }
+    
+    /// Use a palette with the config's color overrides on top; running
+    /// terminals pick it up on their next frame
+    fn apply(state: &mut AppState, name: String, mut palette: ColorPalette) {
+        if let Err(error) = apply_theme_colors(&mut palette, &state.config.theme) {
+            log::warn!("Ignoring theme overrides: {}", error);
+        }
+        state.terminal_theme = TerminalTheme::new(Box::new(palette));
+        state.theme_name = name;
+    }
+    
+    fn file_theme_name(path: &Path) -> String {
+        path.file_stem().map_or_else(|| path.display().to_string(), |stem| stem.to_string_lossy().into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_themes() {
+        for name in ThemeManager::theme_names() {
+            assert!(ThemeManager::bundled_palette(name).is_some(), "{}", name);
+        }
+        let nord = ThemeManager::bundled_palette("nord").unwrap();
+        assert_eq!(nord.background, "#2e3440");
+        assert_eq!(nord.dim_red, "#7e4045");
+        assert!(ThemeManager::bundled_palette("Mauve").is_none());
+    }
+}
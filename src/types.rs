@@ -1,9 +1,19 @@
-use egui_term::TerminalBackend;
+use egui_term::{TerminalBackend, TerminalFont, TerminalTheme};
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::mpsc::{Receiver, Sender};
+use std::time::Instant;
 use crate::ime::korean::KoreanInputState;
+use crate::config_manager::{Config, ConfigManager};
+use crate::keybindings::KeyBindings;
+use crate::action_manager::AppAction;
+use crate::search_manager::PaneSearch;
+use crate::export_manager::ExportDialog;
+use crate::palette_manager::CommandPalette;
+use crate::theme_manager::{ThemePicker, DEFAULT_THEME};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ViewMode {
     Single,
     Grid { 
@@ -16,13 +26,52 @@ pub enum ViewMode {
     },
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SplitDirection {
     Horizontal,
     Vertical,
 }
 
+/// Something the user asked to close
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseTarget {
+    Pane(u64), // terminal_id
+    Tab(u64),  // tab_id
+    OtherTabs(u64), // every tab except this tab_id
+}
+
+/// A close waiting for confirmation because a command is still running
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingClose {
+    pub target: CloseTarget,
+    pub command: String,
+}
+
+/// Most-recently-used tab list shown while the switcher modifier is held
+#[derive(Debug, Clone)]
+pub struct TabSwitcher {
+    pub entries: Vec<SwitcherEntry>, // Most recently used first
+    pub selected: usize,
+    pub held: egui::Modifiers, // Releasing these picks the selected tab
+}
+
 #[derive(Debug, Clone)]
+pub struct SwitcherEntry {
+    pub tab_id: u64,
+    pub title: String,
+    pub working_directory: Option<PathBuf>,
+}
+
+/// Direction for moving focus between panes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FocusDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PanelContent {
     Terminal(u64), // terminal_id
     Split {
@@ -33,10 +82,25 @@ pub enum PanelContent {
     },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TerminalTab {
     pub id: u64,
-    pub title: String,
+    pub title: String, // Fallback when no pane has set a title
+    #[serde(default)]
+    pub pinned_title: Option<String>, // Set by renaming; wins over OSC titles
+    #[serde(default)]
+    pub color: Option<egui::Color32>, // Accent in the tab bar and grid header
+}
+
+impl TerminalTab {
+    pub fn new(id: u64) -> Self {
+        Self {
+            id,
+            title: format!("Terminal {}", id),
+            pinned_title: None,
+            color: None,
+        }
+    }
 }
 
 /// Application state containing all terminal-related data
@@ -50,6 +114,10 @@ pub struct AppState {
     pub tab_layouts: HashMap<u64, PanelContent>, // Layout for each tab
     pub view_mode: ViewMode,
     pub focused_terminal: Option<u64>,
+    pub focus_history: Vec<u64>, // Previously focused terminals, most recent last
+    pub pane_rects: HashMap<u64, egui::Rect>, // Screen rect of each terminal rendered last frame
+    pub zoomed_panes: HashMap<u64, u64>, // tab_id -> terminal shown across the whole tab
+    pub pane_drag: Option<u64>, // Terminal whose pane is being dragged to a new dock position
     
     // Broadcasting
     pub broadcast_mode: bool,
@@ -58,6 +126,34 @@ pub struct AppState {
     // Korean IME support
     pub korean_input_states: HashMap<u64, KoreanInputState>, // Per-terminal Korean input state
     
+    // Configuration
+    pub config: Config,
+    pub config_error: Option<String>, // Last config load error, shown in the status bar
+    pub config_notice: Option<String>, // Settings from the last reload that running terminals ignore
+    pub shell_error: Option<String>, // Why the configured shell failed to start in the last new pane
+    pub config_changes: Receiver<()>, // Signalled by the config file watcher
+    pub terminal_font: TerminalFont,
+    pub font_zoom: f32, // Points added to the configured size in every pane
+    pub pane_font_zoom: HashMap<u64, f32>, // terminal_id -> points on top of the global size
+    pub font_overlay: Option<(Option<u64>, Instant)>, // Pane (or every pane) showing its new size, and since when
+    pub terminal_theme: TerminalTheme,
+    pub theme_name: String, // Shown as the current theme in the picker
+    pub theme_picker: Option<ThemePicker>,
+    pub key_bindings: KeyBindings,
+    pub leader_armed_at: Option<Instant>, // Set while the leader key waits for its command key
+    pub pending_actions: Vec<AppAction>, // Picked from menus while rendering, run after the frame's layout
+    pub pending_close: Option<PendingClose>, // Shown as a confirmation dialog
+    pub terminal_titles: HashMap<u64, String>, // Titles set by programs via OSC 0/2
+    pub renaming_tab: Option<(u64, String)>, // Tab being renamed and its edit buffer
+    pub tab_drag: Option<u64>, // Tab being dragged to a new position in the tab bar
+    pub tab_history: Vec<u64>, // Previously active tabs, most recent last
+    pub tab_switcher: Option<TabSwitcher>,
+    pub pane_searches: HashMap<u64, PaneSearch>, // terminal_id -> open find bar
+    pub export_dialog: Option<ExportDialog>,
+    pub command_palette: Option<CommandPalette>,
+    pub paste_requested: bool, // Pane menu asked for the clipboard; its text arrives as a Paste event
+    pub scrollback_memory: Option<(usize, Instant)>, // Grid memory of all terminals, and when it was measured
+    
     // Communication
     pub pty_proxy_receiver: Receiver<(u64, egui_term::PtyEvent)>,
     pub pty_proxy_sender: Sender<(u64, egui_term::PtyEvent)>,
@@ -69,7 +165,7 @@ impl AppState {
         let (pty_proxy_sender, pty_proxy_receiver) = std::sync::mpsc::channel();
         let egui_ctx = cc.egui_ctx.clone();
         
-        Self {
+        let mut state = Self {
             tabs: HashMap::new(),
             tab_order: Vec::new(),
             active_tab_id: 0,
@@ -79,32 +175,86 @@ impl AppState {
             tab_layouts: HashMap::new(),
             view_mode: ViewMode::Single,
             focused_terminal: None,
+            focus_history: Vec::new(),
+            pane_rects: HashMap::new(),
+            zoomed_panes: HashMap::new(),
+            pane_drag: None,
             broadcast_mode: false,
             selected_terminals: HashSet::new(),
             korean_input_states: HashMap::new(),
+            config: Config::default(),
+            config_error: None,
+            config_notice: None,
+            shell_error: None,
+            config_changes: ConfigManager::watch(egui_ctx.clone()),
+            terminal_font: TerminalFont::default(),
+            font_zoom: 0.0,
+            pane_font_zoom: HashMap::new(),
+            font_overlay: None,
+            terminal_theme: TerminalTheme::default(),
+            theme_name: DEFAULT_THEME.to_string(),
+            theme_picker: None,
+            key_bindings: KeyBindings::default(),
+            leader_armed_at: None,
+            pending_actions: Vec::new(),
+            pending_close: None,
+            terminal_titles: HashMap::new(),
+            renaming_tab: None,
+            tab_drag: None,
+            tab_history: Vec::new(),
+            tab_switcher: None,
+            pane_searches: HashMap::new(),
+            export_dialog: None,
+            command_palette: None,
+            paste_requested: false,
+            scrollback_memory: None,
             pty_proxy_receiver,
             pty_proxy_sender,
             egui_ctx,
-        }
+        };
+        
+        ConfigManager::load(&mut state);
+        state
     }
     
     pub fn create_terminal(&mut self) -> u64 {
-        let system_shell = std::env::var("SHELL")
-            .unwrap_or_else(|_| "/bin/bash".to_string());
-            
+        self.create_terminal_with_cwd(None)
+    }
+    
+    /// Create a terminal starting in `working_directory`, or the configured directory when `None`
+    pub fn create_terminal_with_cwd(&mut self, working_directory: Option<PathBuf>) -> u64 {
         let terminal_id = self.next_terminal_id;
         self.next_terminal_id += 1;
         
-        let terminal_backend = TerminalBackend::new(
+        let mut settings = ConfigManager::backend_settings(&self.config);
+        if let Some(dir) = working_directory.filter(|dir| dir.is_dir()) {
+            settings.working_directory = Some(dir);
+        }
+        
+        // A configured program or directory that fails falls back to the login shell
+        let terminal_backend = match TerminalBackend::new(
             terminal_id,
             self.egui_ctx.clone(),
             self.pty_proxy_sender.clone(),
-            egui_term::BackendSettings {
-                shell: system_shell,
-                ..Default::default()
-            },
-        )
-        .unwrap();
+            settings,
+        ) {
+            Ok(terminal_backend) => {
+                self.shell_error = None;
+                terminal_backend
+            }
+            Err(e) => {
+                let fallback = ConfigManager::default_backend_settings(&self.config);
+                log::warn!("Failed to start the configured shell: {}", e);
+                self.shell_error = Some(format!("Shell failed to start ({}), using {}", e, fallback.shell));
+                TerminalBackend::new(
+                    terminal_id,
+                    self.egui_ctx.clone(),
+                    self.pty_proxy_sender.clone(),
+                    fallback,
+                )
+                .expect("Failed to start the default shell")
+            }
+        };
 
         self.terminals.insert(terminal_id, terminal_backend);
         self.korean_input_states.insert(terminal_id, KoreanInputState::new());
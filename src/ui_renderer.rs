@@ -1,10 +1,28 @@
-use crate::types::{AppState, ViewMode, PanelContent, SplitDirection};
-use crate::tab_manager::TabManager;
+use crate::types::{AppState, ViewMode, PanelContent, SplitDirection, FocusDirection, CloseTarget};
+use crate::tab_manager::{TabManager, TAB_COLORS};
 use crate::grid_manager::GridManager;
 use crate::broadcast_manager::BroadcastManager;
+use crate::split_manager::{SplitManager, MAX_SPLIT_RATIO, MIN_SPLIT_RATIO};
+use crate::action_manager::{ActionManager, AppAction};
+use crate::search_manager::SearchManager;
+use crate::export_manager::ExportManager;
+use crate::copy_mode_manager::CopyModeManager;
+use crate::palette_manager::PaletteManager;
+use crate::font_manager::FontManager;
+use crate::theme_manager::ThemeManager;
+use crate::config_manager::collapse_home;
 use crate::ime::cjk;
 use egui::{Ui, Rect, Vec2, Pos2, FontId, Align2};
-use egui_term::TerminalView;
+use egui_term::{ExportFormat, TerminalView};
+
+/// Height of the strip along a pane's top border that drags the pane
+const PANE_HANDLE_HEIGHT: f32 = 4.0;
+
+/// How often the status bar re-measures the terminals' grid memory
+const MEMORY_REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Pane border and status bar label of copy mode
+const COPY_MODE_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 165, 0);
 
 pub struct UiRenderer;
 
@@ -14,18 +32,39 @@ impl UiRenderer {
         ui.horizontal(|ui| {
             let mut tab_to_close = None;
             let mut tab_to_activate = None;
+            let mut tab_rects = Vec::new();
             
             // Use tab_order to maintain consistent order
-            for &tab_id in &state.tab_order {
+            for (index, tab_id) in state.tab_order.clone().into_iter().enumerate() {
                 if let Some(tab) = state.tabs.get(&tab_id) {
                     let is_active = tab_id == state.active_tab_id;
+                    let title = TabManager::display_title(state, tab_id, tab);
+                    let color = tab.color;
                     
-                    ui.group(|ui| {
+                    let group = ui.group(|ui| {
                         ui.horizontal(|ui| {
-                            let tab_response = ui.selectable_label(is_active, &tab.title);
+                            if let Some(color) = color {
+                                let (accent_rect, _) = ui.allocate_exact_size(Vec2::new(4.0, 16.0), egui::Sense::hover());
+                                ui.painter().rect_filled(accent_rect, 1.0, color);
+                            }
                             
-                            if tab_response.clicked() {
-                                tab_to_activate = Some(tab_id);
+                            if state.renaming_tab.as_ref().is_some_and(|(id, _)| *id == tab_id) {
+                                Self::render_tab_rename(state, ui, None);
+                            } else {
+                                let tab_response = ui.selectable_label(is_active, title)
+                                    .interact(egui::Sense::drag());
+                                
+                                if tab_response.clicked() {
+                                    tab_to_activate = Some(tab_id);
+                                }
+                                if tab_response.drag_started() {
+                                    state.tab_drag = Some(tab_id);
+                                }
+                                if tab_response.double_clicked() {
+                                    TabManager::start_rename(state, tab_id);
+                                }
+                                
+                                tab_response.context_menu(|ui| Self::render_tab_menu(state, ui, tab_id, index));
                             }
                             
                             // Close button
@@ -34,9 +73,12 @@ impl UiRenderer {
                             }
                         });
                     });
+                    tab_rects.push(group.response.rect);
                 }
             }
             
+            Self::render_tab_drag(state, ui, &tab_rects);
+            
             // New tab button
             if ui.button("+").clicked() {
                 TabManager::create_new_tab(state);
@@ -48,14 +90,141 @@ impl UiRenderer {
             }
             
             if let Some(tab_id) = tab_to_close {
-                TabManager::close_tab(state, tab_id);
+                ActionManager::request_close(state, CloseTarget::Tab(tab_id));
             }
         });
     }
     
+    /// Show where a dragged tab will land and move it there on release
+    fn render_tab_drag(state: &mut AppState, ui: &mut Ui, tab_rects: &[Rect]) {
+        let (Some(tab_id), Some(first), Some(last)) = (state.tab_drag, tab_rects.first(), tab_rects.last()) else {
+            return;
+        };
+        let (pointer, released) = ui.input(|i| (i.pointer.interact_pos(), !i.pointer.primary_down()));
+        let Some(pointer) = pointer else {
+            if released {
+                state.tab_drag = None;
+            }
+            return;
+        };
+        
+        // Gap i sits before tab i; tabs whose middle is left of the pointer come first
+        let gap = tab_rects.iter().filter(|rect| rect.center().x < pointer.x).count();
+        
+        if released {
+            state.tab_drag = None;
+            TabManager::move_tab_to_gap(state, tab_id, gap);
+            return;
+        }
+        
+        let x = match gap {
+            0 => first.min.x - 2.0,
+            gap if gap == tab_rects.len() => last.max.x + 2.0,
+            gap => (tab_rects[gap - 1].max.x + tab_rects[gap].min.x) / 2.0,
+        };
+        ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+        ui.painter().vline(
+            x,
+            first.y_range(),
+            egui::Stroke::new(2.0, egui::Color32::from_rgb(0, 150, 255)),
+        );
+    }
+    
+    /// Inline title editor; Enter or clicking away pins the title, Escape cancels
+    fn render_tab_rename(state: &mut AppState, ui: &mut Ui, rect: Option<Rect>) {
+        let Some((tab_id, text)) = state.renaming_tab.as_mut() else {
+            return;
+        };
+        let edit = egui::TextEdit::singleline(text)
+            .id(TabManager::rename_id(*tab_id))
+            .desired_width(140.0);
+        let response = match rect {
+            Some(rect) => ui.put(rect, edit),
+            None => ui.add(edit),
+        };
+        
+        if response.lost_focus() {
+            let cancelled = ui.input(|i| i.key_pressed(egui::Key::Escape));
+            TabManager::finish_rename(state, !cancelled);
+        } else if !response.has_focus() {
+            // Just opened: take the keyboard
+            response.request_focus();
+        }
+    }
+    
+    /// Right-click menu of a tab
+    fn render_tab_menu(state: &mut AppState, ui: &mut Ui, tab_id: u64, index: usize) {
+        let Some(tab) = state.tabs.get(&tab_id) else {
+            return;
+        };
+        let is_pinned = tab.pinned_title.is_some();
+        let current_color = tab.color;
+        
+        if ui.button("Rename…").clicked() {
+            TabManager::start_rename(state, tab_id);
+            ui.close();
+        }
+        if ui.add_enabled(is_pinned, egui::Button::new("Use Automatic Title")).clicked() {
+            TabManager::set_pinned_title(state, tab_id, None);
+            ui.close();
+        }
+        ui.menu_button("Color", |ui| {
+            if ui.selectable_label(current_color.is_none(), "None").clicked() {
+                TabManager::set_color(state, tab_id, None);
+                ui.close();
+            }
+            for &(name, color) in TAB_COLORS {
+                let label = egui::RichText::new(format!("■ {}", name)).color(color);
+                if ui.selectable_label(current_color == Some(color), label).clicked() {
+                    TabManager::set_color(state, tab_id, Some(color));
+                    ui.close();
+                }
+            }
+        });
+        
+        ui.separator();
+        let mut picked = None;
+        if ui.button("Duplicate").clicked() {
+            picked = Some(AppAction::DuplicateTab);
+        }
+        if ui.add_enabled(state.tabs.len() > 1, egui::Button::new("Close Others")).clicked() {
+            picked = Some(AppAction::CloseOtherTabs);
+        }
+        if ui.add_enabled(tab_id != state.active_tab_id, egui::Button::new("Join into Current Tab")).clicked() {
+            state.pending_actions.push(AppAction::JoinTab(index));
+            ui.close();
+        }
+        
+        if let Some(action) = picked {
+            // Actions apply to the active tab
+            TabManager::switch_to_tab(state, tab_id);
+            state.pending_actions.push(action);
+            ui.close();
+        }
+    }
+    
     /// Render the status bar
-    pub fn render_status_bar(state: &AppState, ui: &mut Ui) {
+    pub fn render_status_bar(state: &mut AppState, ui: &mut Ui) {
+        // Measuring locks every terminal, so reuse the last value for a while
+        let memory = match state.scrollback_memory {
+            Some((memory, measured_at)) if measured_at.elapsed() < MEMORY_REFRESH_INTERVAL => memory,
+            _ => {
+                let memory = state.terminals.values().map(|terminal| terminal.memory_usage()).sum();
+                state.scrollback_memory = Some((memory, std::time::Instant::now()));
+                memory
+            }
+        };
+        
         ui.horizontal(|ui| {
+            // Leader key waiting for its command key
+            if state.leader_armed_at.is_some() {
+                let leader = state.key_bindings.leader()
+                    .map(|shortcut| ui.ctx().format_shortcut(&shortcut))
+                    .unwrap_or_default();
+                ui.colored_label(egui::Color32::from_rgb(255, 200, 0), format!("⏵ LEADER {}", leader));
+                ui.separator();
+            }
+            
             // Broadcast status
             if BroadcastManager::is_broadcast_mode_active(state) {
                 ui.colored_label(egui::Color32::from_rgb(255, 100, 100), "☀ BROADCAST");
@@ -78,9 +247,54 @@ impl UiRenderer {
             if let Some(focused) = state.focused_terminal {
                 ui.label(format!("Focus: Terminal {}", focused));
             }
+            
+            // Memory held by every terminal's grid and scrollback
+            ui.separator();
+            ui.label(format!("Scrollback: {}", Self::format_memory(memory)))
+                .on_hover_text(format!("Approximate grid memory of {} terminals", state.terminals.len()));
+            
+            // Copy mode on the focused pane
+            if state.focused_terminal.is_some_and(|terminal_id| CopyModeManager::is_active(state, terminal_id)) {
+                ui.separator();
+                ui.colored_label(COPY_MODE_COLOR, "COPY");
+            }
+            
+            // Zoomed pane in the active tab
+            if SplitManager::zoomed_terminal(state, state.active_tab_id).is_some() {
+                ui.separator();
+                ui.colored_label(egui::Color32::from_rgb(0, 150, 255), "[Z] ZOOMED");
+            }
+            
+            // Config settings the running terminals did not pick up
+            if let Some(notice) = &state.config_notice {
+                ui.separator();
+                ui.label(format!("ⓘ {}", notice));
+            }
+            
+            // Config errors
+            if let Some(error) = &state.config_error {
+                ui.separator();
+                ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("⚠ {}", error));
+            }
+            if let Some(error) = &state.shell_error {
+                ui.separator();
+                ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("⚠ {}", error));
+            }
         });
     }
     
+    /// Byte count as KB/MB/GB for the status bar
+    fn format_memory(bytes: usize) -> String {
+        const UNITS: [&str; 3] = ["KB", "MB", "GB"];
+        let mut value = bytes as f64 / 1024.0;
+        let mut unit = 0;
+        while value >= 1024.0 && unit < UNITS.len() - 1 {
+            value /= 1024.0;
+            unit += 1;
+        }
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+    
     /// Render panel content (terminal or split)
     pub fn render_panel_content(state: &mut AppState, ui: &mut Ui, content: &mut PanelContent, available_rect: Rect) {
         match content {
@@ -97,10 +311,15 @@ impl UiRenderer {
     fn render_terminal_panel(state: &mut AppState, ui: &mut Ui, terminal_id: u64, available_rect: Rect) {
         let is_focused = state.focused_terminal == Some(terminal_id);
         let is_selected_for_broadcast = BroadcastManager::is_terminal_selected(state, terminal_id);
+        let is_copy_mode = CopyModeManager::is_active(state, terminal_id);
+        let font = FontManager::font(state, terminal_id);
+        state.pane_rects.insert(terminal_id, available_rect);
         
         if let Some(terminal_backend) = state.terminals.get_mut(&terminal_id) {
             // Add visual focus indicator and broadcast selection
-            let border_color = if is_focused {
+            let border_color = if is_copy_mode {
+                COPY_MODE_COLOR
+            } else if is_focused {
                 egui::Color32::from_rgb(0, 150, 255) // Blue for focused
             } else if is_selected_for_broadcast {
                 egui::Color32::from_rgb(255, 100, 100) // Red for broadcast selected
@@ -122,17 +341,36 @@ impl UiRenderer {
                 .unwrap_or(false);
             
             let terminal = TerminalView::new(ui, terminal_backend)
+                .set_font(font)
+                .set_theme(state.terminal_theme.clone())
+                .set_block_selection_modifiers(state.key_bindings.block_selection)
                 .set_focus(false) // Disable focus on TerminalView to prevent mouse dependency
                 .set_size(Vec2::new(available_rect.width(), available_rect.height()));
             
             // Render terminal and check for clicks
-            ui.scope_builder(egui::UiBuilder::new().max_rect(available_rect), |ui| {
+            let response = ui.scope_builder(egui::UiBuilder::new().max_rect(available_rect), |ui| {
                 ui.add(terminal)
-            });
+            }).inner;
+            response.context_menu(|ui| Self::render_pane_menu(state, ui, terminal_id));
             
             // Render CJK double-wide cursor overlay (includes Korean composition)
             Self::render_cjk_cursor_overlay(state, ui, terminal_id, available_rect);
             
+            Self::render_find_bar(state, ui, terminal_id, available_rect);
+            
+            Self::render_font_overlay(state, ui, terminal_id, available_rect);
+            
+            // The top border doubles as a handle for re-docking the pane
+            let handle_rect = Rect::from_min_size(available_rect.min, Vec2::new(available_rect.width(), PANE_HANDLE_HEIGHT));
+            let handle = ui.interact(handle_rect, ui.id().with(("pane_handle", terminal_id)), egui::Sense::drag());
+            if handle.hovered() || handle.dragged() {
+                ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
+                ui.painter().rect_filled(handle_rect, 0.0, border_color);
+            }
+            if handle.drag_started() {
+                state.pane_drag = Some(terminal_id);
+            }
+            
             // Check if the terminal area was clicked
             if ui.input(|i| i.pointer.any_click()) {
                 if let Some(pos) = ui.input(|i| i.pointer.interact_pos()) {
@@ -144,6 +382,467 @@ impl UiRenderer {
         }
     }
     
+    /// Font size and cell size shown in the middle of a pane for a moment after zooming
+    fn render_font_overlay(state: &AppState, ui: &mut Ui, terminal_id: u64, pane_rect: Rect) {
+        if !FontManager::overlay_visible(state, ui.ctx(), terminal_id) {
+            return;
+        }
+        let Some(terminal) = state.terminals.get(&terminal_id) else {
+            return;
+        };
+        let cell = terminal.last_content().terminal_size;
+        let text = format!(
+            "{:.0} pt · {}×{} px cells",
+            FontManager::font_size(state, terminal_id),
+            cell.cell_width,
+            cell.cell_height,
+        );
+        
+        egui::Area::new(ui.id().with(("font_overlay", terminal_id)))
+            .order(egui::Order::Foreground)
+            .fixed_pos(pane_rect.center())
+            .pivot(Align2::CENTER_CENTER)
+            .interactable(false)
+            .show(ui.ctx(), |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(egui::RichText::new(text).monospace().size(16.0));
+                });
+            });
+    }
+    
+    /// Find bar in the pane's top right corner: Enter searches upwards,
+    /// Shift+Enter downwards, Escape closes
+    fn render_find_bar(state: &mut AppState, ui: &mut Ui, terminal_id: u64, pane_rect: Rect) {
+        let status = state.terminals.get(&terminal_id).and_then(|terminal| terminal.search_status());
+        let Some(search) = state.pane_searches.get_mut(&terminal_id) else {
+            return;
+        };
+        
+        let bar_size = Vec2::new(pane_rect.width().min(400.0), 32.0);
+        let bar_rect = Rect::from_min_size(Pos2::new(pane_rect.max.x - bar_size.x, pane_rect.min.y + PANE_HANDLE_HEIGHT), bar_size);
+        let mut changed = false;
+        let mut step = None;
+        let mut close = false;
+        
+        ui.scope_builder(egui::UiBuilder::new().max_rect(bar_rect), |ui| {
+            egui::Frame::popup(ui.style()).inner_margin(4.0).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    let edit = ui.add(egui::TextEdit::singleline(&mut search.query)
+                        .id(SearchManager::input_id(terminal_id))
+                        .hint_text("Find")
+                        .desired_width(140.0));
+                    changed |= edit.changed();
+                    if edit.lost_focus() {
+                        let (enter, shift, escape) = ui.input(|i| (i.key_pressed(egui::Key::Enter), i.modifiers.shift, i.key_pressed(egui::Key::Escape)));
+                        if escape {
+                            close = true;
+                        } else if enter {
+                            step = Some(shift);
+                            edit.request_focus();
+                        }
+                    }
+                    
+                    changed |= ui.toggle_value(&mut search.options.regex, ".*").on_hover_text("Regular expression").changed();
+                    changed |= ui.toggle_value(&mut search.options.case_sensitive, "Aa").on_hover_text("Match case").changed();
+                    changed |= ui.toggle_value(&mut search.options.whole_word, "W").on_hover_text("Whole word").changed();
+                    
+                    match (&search.error, status) {
+                        (Some(error), _) => {
+                            ui.colored_label(egui::Color32::from_rgb(255, 100, 100), "Invalid").on_hover_text(error.as_str());
+                        }
+                        (None, Some(status)) => {
+                            let total = if status.complete { status.total.to_string() } else { format!("{}+", status.total) };
+                            let current = status.current.map_or("0".to_string(), |current| current.to_string());
+                            ui.label(format!("{} of {}", current, total));
+                        }
+                        (None, None) => {}
+                    }
+                    
+                    if ui.small_button("↑").on_hover_text("Previous match (Enter)").clicked() {
+                        step = Some(false);
+                    }
+                    if ui.small_button("↓").on_hover_text("Next match (Shift+Enter)").clicked() {
+                        step = Some(true);
+                    }
+                    if ui.small_button("×").clicked() {
+                        close = true;
+                    }
+                });
+            });
+        });
+        
+        if close {
+            SearchManager::close_find_bar(state, terminal_id);
+        } else if changed {
+            SearchManager::update_search(state, terminal_id);
+        } else if let Some(forward) = step {
+            SearchManager::step(state, terminal_id, forward);
+        }
+    }
+    
+    /// Right-click menu of a pane; picks run through the action dispatcher
+    fn render_pane_menu(state: &mut AppState, ui: &mut Ui, terminal_id: u64) {
+        let Some(tab_id) = TabManager::find_tab_of_terminal(state, terminal_id) else {
+            return;
+        };
+        let is_split = state.tab_layouts.get(&tab_id)
+            .is_some_and(|layout| SplitManager::count_terminals_in_layout(layout) > 1);
+        let is_zoomed = SplitManager::zoomed_terminal(state, tab_id) == Some(terminal_id);
+        let is_copy_mode = CopyModeManager::is_active(state, terminal_id);
+        let mut is_broadcast = BroadcastManager::is_terminal_selected(state, terminal_id);
+        // The selection text is only built when Copy is clicked; the last
+        // rendered frame tells whether there is one
+        let has_selection = state.terminals.get(&terminal_id)
+            .is_some_and(|terminal| terminal.last_content().selectable_range.is_some());
+        let mut picked = None;
+        
+        if ui.add_enabled(has_selection, egui::Button::new("Copy")).clicked() {
+            if let Some(terminal) = state.terminals.get(&terminal_id) {
+                ui.ctx().copy_text(terminal.selectable_content());
+            }
+            ui.close();
+        }
+        if ui.add_enabled(!is_copy_mode, egui::Button::new("Paste")).clicked() {
+            // The clipboard text comes back as a Paste event for the focused pane
+            state.focused_terminal = Some(terminal_id);
+            state.paste_requested = true;
+            ui.ctx().send_viewport_cmd(egui::ViewportCommand::RequestPaste);
+            ui.close();
+        }
+        ui.separator();
+        if ui.button("Split Right").clicked() {
+            picked = Some(AppAction::SplitVertical);
+        }
+        if ui.button("Split Down").clicked() {
+            picked = Some(AppAction::SplitHorizontal);
+        }
+        if ui.add_enabled(is_split, egui::Button::selectable(is_zoomed, "Zoom")).clicked() {
+            picked = Some(AppAction::ToggleZoom);
+        }
+        if ui.button("Close Pane").clicked() {
+            picked = Some(AppAction::ClosePane);
+        }
+        ui.separator();
+        if ui.button("Find…").clicked() {
+            picked = Some(AppAction::Find);
+        }
+        if ui.button("Clear Scrollback").clicked() {
+            picked = Some(AppAction::ClearScrollback);
+        }
+        if ui.checkbox(&mut is_broadcast, "Broadcast to This Pane").clicked() {
+            picked = Some(AppAction::TogglePaneBroadcast);
+        }
+        ui.separator();
+        if ui.button("Save Output As…").clicked() {
+            picked = Some(AppAction::SaveOutput);
+        }
+        ui.separator();
+        if ui.add_enabled(is_split, egui::Button::new("Move to New Tab")).clicked() {
+            picked = Some(AppAction::BreakPane);
+        }
+        ui.add_enabled_ui(state.tabs.len() > 1, |ui| {
+            ui.menu_button("Join Tab Here", |ui| {
+                for (index, other_tab_id) in state.tab_order.iter().enumerate() {
+                    if *other_tab_id == tab_id {
+                        continue;
+                    }
+                    if let Some(tab) = state.tabs.get(other_tab_id) {
+                        if ui.button(TabManager::display_title(state, *other_tab_id, tab)).clicked() {
+                            picked = Some(AppAction::JoinTab(index));
+                        }
+                    }
+                }
+            });
+        });
+        
+        if let Some(action) = picked {
+            // Actions apply to the focused pane
+            state.focused_terminal = Some(terminal_id);
+            state.pending_actions.push(action);
+            ui.close();
+        }
+    }
+    
+    /// Ask before closing a pane or tab whose command is still running
+    pub fn render_close_confirmation(state: &mut AppState, ctx: &egui::Context) {
+        let Some(pending) = &state.pending_close else {
+            return;
+        };
+        let (what, place) = match pending.target {
+            CloseTarget::Pane(_) => ("pane", "this pane"),
+            CloseTarget::Tab(_) => ("tab", "this tab"),
+            CloseTarget::OtherTabs(_) => ("other tabs", "another tab"),
+        };
+        let message = format!("\"{}\" is still running in {}.", pending.command, place);
+        let mut answer = None;
+        
+        let modal = egui::Modal::new(egui::Id::new("close_confirmation")).show(ctx, |ui| {
+            ui.heading(format!("Close {}?", what));
+            ui.label(message);
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if ui.button("Close").clicked() {
+                    answer = Some(true);
+                }
+                if ui.button("Cancel").clicked() {
+                    answer = Some(false);
+                }
+            });
+        });
+        if modal.should_close() {
+            answer.get_or_insert(false);
+        }
+        
+        if let Some(confirmed) = answer {
+            ActionManager::confirm_close(state, confirmed);
+        }
+    }
+    
+    /// File name and format prompt for "Save Output As…"
+    pub fn render_export_dialog(state: &mut AppState, ctx: &egui::Context) {
+        ExportManager::poll_save(state);
+        let Some(dialog) = &mut state.export_dialog else {
+            return;
+        };
+        let mut format = dialog.format;
+        let mut answer = None;
+        let mut path_changed = false;
+        let saving = dialog.saving.is_some();
+        
+        let modal = egui::Modal::new(egui::Id::new("export_dialog")).show(ctx, |ui| {
+            ui.heading("Save Output As");
+            ui.horizontal(|ui| {
+                for option in ExportFormat::ALL {
+                    ui.radio_value(&mut format, option, option.label());
+                }
+            });
+            let path = ui.add_enabled(
+                !saving,
+                egui::TextEdit::singleline(&mut dialog.path).desired_width(420.0),
+            );
+            path_changed = path.changed();
+            if !saving && !path.has_focus() && !path.lost_focus() {
+                path.request_focus();
+            }
+            if path.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                answer = Some(true);
+            }
+            if dialog.replace {
+                ui.colored_label(egui::Color32::from_rgb(255, 200, 100), "This file already exists. Replace it?");
+            }
+            if let Some(error) = &dialog.error {
+                ui.colored_label(egui::Color32::from_rgb(255, 100, 100), error);
+            }
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                let label = if dialog.replace { "Replace" } else { "Save" };
+                if ui.add_enabled(!saving, egui::Button::new(label)).clicked() {
+                    answer = Some(true);
+                }
+                if ui.button("Cancel").clicked() {
+                    answer = Some(false);
+                }
+                if saving {
+                    ui.spinner();
+                }
+            });
+        });
+        if modal.should_close() {
+            answer.get_or_insert(false);
+        }
+        
+        if format != dialog.format {
+            ExportManager::set_format(state, format);
+        }
+        if path_changed {
+            ExportManager::path_changed(state);
+        }
+        match answer {
+            Some(true) => ExportManager::save(state),
+            Some(false) => state.export_dialog = None,
+            None => {}
+        }
+    }
+    
+    /// MRU tab list shown while the switcher modifier is held
+    pub fn render_tab_switcher(state: &AppState, ctx: &egui::Context) {
+        let Some(switcher) = &state.tab_switcher else {
+            return;
+        };
+        egui::Area::new(egui::Id::new("tab_switcher"))
+            .order(egui::Order::Foreground)
+            .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_min_width(320.0);
+                    for (index, entry) in switcher.entries.iter().enumerate() {
+                        let selected = index == switcher.selected;
+                        ui.add(egui::Button::selectable(selected, egui::RichText::new(&entry.title).strong())
+                            .min_size(Vec2::new(ui.available_width(), 0.0)));
+                        if let Some(cwd) = &entry.working_directory {
+                            ui.label(egui::RichText::new(collapse_home(cwd)).weak().small());
+                        }
+                    }
+                });
+            });
+    }
+    
+    /// Theme picker: clicking a theme re-themes every pane at once
+    pub fn render_theme_picker(state: &mut AppState, ctx: &egui::Context) {
+        let Some(picker) = &mut state.theme_picker else {
+            return;
+        };
+        let current = state.theme_name.clone();
+        let mut picked = None;
+        let mut import = false;
+        let mut open = true;
+        
+        egui::Window::new("Theme")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::RIGHT_TOP, Vec2::new(-16.0, 48.0))
+            .show(ctx, |ui| {
+                for name in ThemeManager::theme_names() {
+                    if ui.selectable_label(name == current, name).clicked() {
+                        picked = Some(name);
+                    }
+                }
+                
+                ui.separator();
+                ui.label("Import alacritty, base16 or iTerm2 scheme:");
+                ui.horizontal(|ui| {
+                    let path = ui.add(egui::TextEdit::singleline(&mut picker.import_path)
+                        .hint_text("~/themes/scheme.itermcolors")
+                        .desired_width(240.0));
+                    let submitted = path.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if ui.button("Import").clicked() || submitted {
+                        import = true;
+                    }
+                });
+                if let Some(error) = &picker.error {
+                    ui.colored_label(egui::Color32::from_rgb(255, 100, 100), error);
+                }
+                ui.label(egui::RichText::new("Set theme.name or theme.file in config.toml to keep it").weak().small());
+            });
+        
+        if let Some(name) = picked {
+            ThemeManager::select(state, name);
+        }
+        if import {
+            ThemeManager::import(state);
+        }
+        if !open || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            state.theme_picker = None;
+        }
+    }
+    
+    /// Command palette: type to filter, arrows move, Enter runs, Escape closes
+    pub fn render_command_palette(state: &mut AppState, ctx: &egui::Context) {
+        if state.command_palette.is_none() {
+            return;
+        }
+        let entries = PaletteManager::matches(state);
+        let (up, down, enter, escape) = ctx.input_mut(|i| (
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+            i.key_pressed(egui::Key::Enter),
+            i.key_pressed(egui::Key::Escape),
+        ));
+        if up || down {
+            PaletteManager::move_selection(state, if up { -1 } else { 1 }, entries.len());
+        }
+        let Some(palette) = &mut state.command_palette else {
+            return;
+        };
+        let selected = palette.selected.min(entries.len().saturating_sub(1));
+        let mut picked = None;
+        
+        let area = egui::Area::new(egui::Id::new("command_palette"))
+            .order(egui::Order::Foreground)
+            .anchor(Align2::CENTER_TOP, Vec2::new(0.0, 60.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_width(480.0);
+                    let query = ui.add(egui::TextEdit::singleline(&mut palette.query)
+                        .hint_text("Type a command, tab or pane…")
+                        .desired_width(f32::INFINITY));
+                    if query.changed() {
+                        palette.selected = 0;
+                    }
+                    if !query.has_focus() && !query.lost_focus() {
+                        query.request_focus();
+                    }
+                    
+                    ui.separator();
+                    egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                        for (index, entry) in entries.iter().enumerate() {
+                            let row = ui.horizontal(|ui| {
+                                let button = ui.add(egui::Button::selectable(index == selected, &entry.label)
+                                    .min_size(Vec2::new(ui.available_width() - 140.0, 0.0)));
+                                ui.label(egui::RichText::new(&entry.detail).weak().small());
+                                button
+                            }).inner;
+                            if index == selected && (up || down) {
+                                row.scroll_to_me(None);
+                            }
+                            if row.clicked() {
+                                picked = Some(entry.target);
+                            }
+                        }
+                        if entries.is_empty() {
+                            ui.label(egui::RichText::new("No matches").weak());
+                        }
+                    });
+                });
+            });
+        let clicked_outside = ctx.input(|i| i.pointer.any_click()) && !area.response.contains_pointer();
+        
+        if enter {
+            picked = picked.or(entries.get(selected).map(|entry| entry.target));
+        }
+        match picked {
+            Some(target) => PaletteManager::run(state, target),
+            None if escape || clicked_outside => state.command_palette = None,
+            None => {}
+        }
+    }
+    
+    /// Preview where a dragged pane will dock and move it on release; runs
+    /// after all panes are laid out so every drop target is known
+    pub fn render_pane_drag(state: &mut AppState, ctx: &egui::Context) {
+        let Some(source) = state.pane_drag else {
+            return;
+        };
+        let (pointer, released) = ctx.input(|i| (i.pointer.interact_pos(), !i.pointer.primary_down()));
+        let target = pointer.and_then(|pos| {
+            state.pane_rects.iter()
+                .find(|(id, rect)| **id != source && rect.contains(pos))
+                .map(|(&id, &rect)| (id, rect, SplitManager::drop_edge(rect, pos)))
+        });
+        
+        if released {
+            state.pane_drag = None;
+            if let Some((target_id, _, edge)) = target {
+                SplitManager::move_pane(state, source, target_id, edge);
+            }
+            return;
+        }
+        
+        ctx.set_cursor_icon(egui::CursorIcon::Grabbing);
+        if let Some((_, rect, edge)) = target {
+            let preview = match edge {
+                FocusDirection::Left => rect.split_left_right_at_fraction(0.5).0,
+                FocusDirection::Right => rect.split_left_right_at_fraction(0.5).1,
+                FocusDirection::Up => rect.split_top_bottom_at_fraction(0.5).0,
+                FocusDirection::Down => rect.split_top_bottom_at_fraction(0.5).1,
+            };
+            let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("pane_drag_preview")));
+            painter.rect_filled(preview, 2.0, egui::Color32::from_rgba_unmultiplied(0, 150, 255, 60));
+            painter.rect_stroke(preview, 2.0, egui::Stroke::new(2.0, egui::Color32::from_rgb(0, 150, 255)), egui::epaint::StrokeKind::Inside);
+        }
+    }
+    
     /// Render a split panel
     fn render_split_panel(
         state: &mut AppState, 
@@ -174,16 +873,7 @@ impl UiRenderer {
         available_rect: Rect,
     ) {
         let split_y = available_rect.min.y + available_rect.height() * *ratio;
-        
-        let first_rect = Rect::from_min_max(
-            available_rect.min,
-            egui::pos2(available_rect.max.x, split_y - 2.0),
-        );
-        
-        let second_rect = Rect::from_min_max(
-            egui::pos2(available_rect.min.x, split_y + 2.0),
-            available_rect.max,
-        );
+        let (first_rect, second_rect) = SplitManager::split_rects(SplitDirection::Horizontal, *ratio, available_rect);
         
         // Create separator area for dragging
         let separator_rect = Rect::from_min_max(
@@ -198,7 +888,7 @@ impl UiRenderer {
         if separator_response.dragged() {
             if let Some(pointer_pos) = ui.input(|i| i.pointer.interact_pos()) {
                 let new_split_y = pointer_pos.y;
-                let new_ratio = ((new_split_y - available_rect.min.y) / available_rect.height()).clamp(0.1, 0.9);
+                let new_ratio = ((new_split_y - available_rect.min.y) / available_rect.height()).clamp(MIN_SPLIT_RATIO, MAX_SPLIT_RATIO);
                 *ratio = new_ratio;
             }
         }
@@ -232,16 +922,7 @@ impl UiRenderer {
         available_rect: Rect,
     ) {
         let split_x = available_rect.min.x + available_rect.width() * *ratio;
-        
-        let first_rect = Rect::from_min_max(
-            available_rect.min,
-            egui::pos2(split_x - 2.0, available_rect.max.y),
-        );
-        
-        let second_rect = Rect::from_min_max(
-            egui::pos2(split_x + 2.0, available_rect.min.y),
-            available_rect.max,
-        );
+        let (first_rect, second_rect) = SplitManager::split_rects(SplitDirection::Vertical, *ratio, available_rect);
         
         // Create separator area for dragging
         let separator_rect = Rect::from_min_max(
@@ -256,7 +937,7 @@ impl UiRenderer {
         if separator_response.dragged() {
             if let Some(pointer_pos) = ui.input(|i| i.pointer.interact_pos()) {
                 let new_split_x = pointer_pos.x;
-                let new_ratio = ((new_split_x - available_rect.min.x) / available_rect.width()).clamp(0.1, 0.9);
+                let new_ratio = ((new_split_x - available_rect.min.x) / available_rect.width()).clamp(MIN_SPLIT_RATIO, MAX_SPLIT_RATIO);
                 *ratio = new_ratio;
             }
         }
@@ -297,7 +978,10 @@ impl UiRenderer {
             
             // Render grid cells
             for (idx, &tab_id) in tab_ids.iter().enumerate() {
-                Self::render_grid_cell(state, ui, tab_id, idx, rows, cols, &col_ratios, &row_ratios, tab_count, available_rect);
+                let cell_rect = GridManager::calculate_cell_rect(
+                    available_rect, rows, cols, &col_ratios, &row_ratios, tab_count, idx
+                );
+                Self::render_grid_cell(state, ui, tab_id, idx, cell_rect);
             }
             
             // Render grid separators
@@ -309,24 +993,9 @@ impl UiRenderer {
     }
     
     /// Render a single grid cell
-    fn render_grid_cell(
-        state: &mut AppState,
-        ui: &mut Ui,
-        tab_id: u64,
-        tab_index: usize,
-        rows: usize,
-        cols: usize,
-        col_ratios: &[f32],
-        row_ratios: &[f32],
-        tab_count: usize,
-        available_rect: Rect,
-    ) {
-        let cell_rect = GridManager::calculate_cell_rect(
-            available_rect, rows, cols, col_ratios, row_ratios, tab_count, tab_index
-        );
-        
+    fn render_grid_cell(state: &mut AppState, ui: &mut Ui, tab_id: u64, tab_index: usize, cell_rect: Rect) {
         // Get tab layout and render it
-        if let Some(tab) = state.tabs.get(&tab_id) {
+        if let Some(tab) = state.tabs.get(&tab_id).cloned() {
             if let Some(layout) = state.tab_layouts.get(&tab_id).cloned() {
                 // Draw tab border
                 let is_active_tab = tab_id == state.active_tab_id;
@@ -371,21 +1040,44 @@ impl UiRenderer {
                     egui::Color32::from_rgb(50, 50, 50), // Dark gray background
                 );
                 
-                // Draw header text
-                ui.painter().text(
-                    header_rect.center(),
-                    egui::Align2::CENTER_CENTER,
-                    &tab.title,
-                    egui::FontId::proportional(12.0),
-                    egui::Color32::WHITE,
-                );
+                if let Some(color) = tab.color {
+                    let accent_rect = Rect::from_min_max(
+                        egui::pos2(header_rect.min.x, header_rect.max.y - 3.0),
+                        header_rect.max,
+                    );
+                    ui.painter().rect_filled(accent_rect, 0.0, color);
+                }
                 
-                // Render the tab's layout in the content area
-                let mut layout_copy = layout.clone();
-                Self::render_panel_content_clipped(state, ui, &mut layout_copy, content_rect);
+                if state.renaming_tab.as_ref().is_some_and(|(id, _)| *id == tab_id) {
+                    Self::render_tab_rename(state, ui, Some(header_rect.shrink2(egui::vec2(8.0, 2.0))));
+                } else {
+                    // Draw header text
+                    ui.painter().text(
+                        header_rect.center(),
+                        egui::Align2::CENTER_CENTER,
+                        TabManager::display_title(state, tab_id, &tab),
+                        egui::FontId::proportional(12.0),
+                        egui::Color32::WHITE,
+                    );
+                    
+                    let header_response = ui.interact(header_rect, ui.id().with(("grid_header", tab_id)), egui::Sense::click());
+                    if header_response.double_clicked() {
+                        TabManager::start_rename(state, tab_id);
+                    }
+                    header_response.context_menu(|ui| Self::render_tab_menu(state, ui, tab_id, tab_index));
+                }
                 
-                // Update the layout if it was modified
-                state.tab_layouts.insert(tab_id, layout_copy);
+                if let Some(terminal_id) = SplitManager::zoomed_terminal(state, tab_id) {
+                    // Zoomed pane fills the cell, the stored layout stays untouched
+                    Self::render_panel_content_clipped(state, ui, &mut PanelContent::Terminal(terminal_id), content_rect);
+                } else {
+                    // Render the tab's layout in the content area
+                    let mut layout_copy = layout.clone();
+                    Self::render_panel_content_clipped(state, ui, &mut layout_copy, content_rect);
+                    
+                    // Update the layout if it was modified
+                    state.tab_layouts.insert(tab_id, layout_copy);
+                }
             }
         }
     }
@@ -526,7 +1218,7 @@ impl UiRenderer {
             
             if should_show_double {
                 let cursor_width = if composing_char.is_some() || 
-                    char_at_cursor.map_or(false, cjk::is_double_width_char) {
+                    char_at_cursor.is_some_and(cjk::is_double_width_char) {
                     single_char_width * 2.0
                 } else {
                     single_char_width
@@ -545,7 +1237,7 @@ impl UiRenderer {
                         cursor_pos,
                         Vec2::new(cursor_width, char_height),
                     ),
-                    egui::Rounding::ZERO,
+                    egui::CornerRadius::ZERO,
                     cursor_bg_color,
                 );
                 
@@ -571,7 +1263,7 @@ impl UiRenderer {
                         cursor_pos,
                         Vec2::new(cursor_width, char_height),
                     ),
-                    egui::Rounding::ZERO,
+                    egui::CornerRadius::ZERO,
                     egui::Stroke::new(1.0, egui::Color32::from_rgb(128, 128, 128)),
                     egui::epaint::StrokeKind::Outside,
                 );